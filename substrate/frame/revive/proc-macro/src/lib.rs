@@ -20,9 +20,11 @@
 //! Most likely you should use the [`#[define_env]`][`macro@define_env`] attribute macro which hides
 //! boilerplate of defining external environment for a wasm module.
 
+use blake2::{digest::consts::U32, Blake2b, Digest};
 use proc_macro::TokenStream;
-use proc_macro2::{Literal, Span, TokenStream as TokenStream2};
+use proc_macro2::{Literal, TokenStream as TokenStream2};
 use quote::{quote, ToTokens};
+use std::collections::BTreeMap;
 use syn::{parse_quote, punctuated::Punctuated, spanned::Spanned, token::Comma, FnArg, Ident};
 
 /// Defines a host functions set that can be imported by contract wasm code.
@@ -43,11 +45,11 @@ use syn::{parse_quote, punctuated::Punctuated, spanned::Spanned, token::Comma, F
 /// 	}
 /// }
 /// ```
-/// This example will expand to the `foo()` defined in the wasm module named `seal0`. This is
-/// because the module `seal0` is the default when no module is specified.
+/// This example will expand to the `foo()` syscall which is exported under the symbol `foo`. This
+/// is because version `0` is the default when no version is specified.
 ///
-/// To define a host function in `seal2` and `seal3` modules, it should be annotated with the
-/// appropriate attribute as follows:
+/// To define a new version of a host function, it should be annotated with the appropriate
+/// attribute as follows:
 ///
 /// ## Example
 ///
@@ -71,14 +73,63 @@ use syn::{parse_quote, punctuated::Punctuated, spanned::Spanned, token::Comma, F
 ///
 ///
 /// In this example, the following host functions will be generated by the macro:
-/// - `foo()` in module `seal1`,
-/// - `seal_foo()` in module `seal1`,
-/// - `bar()` in module `seal42`.
+/// - `foo()` exported as `seal2_foo`,
+/// - `bar()` exported as `seal3_bar`.
+///
+/// Multiple versions of the same host function can be defined side by side. `resolve_symbol()`
+/// returns the symbol of the highest callable version.
+///
+/// The export symbol can also be set explicitly with `#[symbol = "<ascii>"]`, which takes
+/// precedence over the function name and version. The generated Rust items are named after the
+/// symbol with every character other than ASCII letters and digits replaced by `_`, so this must
+/// result in a valid identifier: the symbol can't start with a digit or be a keyword.
 ///
 /// Only following return types are allowed for the host functions defined with the macro:
 /// - `Result<(), TrapReason>`,
 /// - `Result<ReturnErrorCode, TrapReason>`,
-/// - `Result<u32, TrapReason>`.
+/// - `Result<u32, TrapReason>`,
+/// - `Result<i32, TrapReason>`,
+/// - `Result<u64, TrapReason>`,
+/// - `Result<i64, TrapReason>`,
+/// - `Result<(u32, u32), TrapReason>`.
+///
+/// Instead of `ReturnErrorCode` a host function can return its own error code enum which
+/// implements `Into<ReturnErrorCode>`. The enum must be named by an UpperCamelCase path without
+/// generic arguments; any other type is rejected. The guest still receives a `ReturnErrorCode`:
+///
+/// ```nocompile
+/// #[define_env]
+/// pub mod some_env {
+/// 	fn lookup(&mut self, memory: &mut M, key_ptr: u32) -> Result<LookupResult, TrapReason> {
+/// 		Ok(LookupResult::NotFound)
+/// 	}
+/// }
+/// ```
+///
+/// A signed result is written into the output register with its two's complement bit pattern:
+///
+/// ```nocompile
+/// #[define_env]
+/// pub mod some_env {
+/// 	fn minus_one(&mut self, memory: &mut M) -> Result<i32, TrapReason> {
+/// 		// The guest reads `u32::MAX` from the output register which it casts back to `-1i32`.
+/// 		Ok(-1i32)
+/// 	}
+/// }
+/// ```
+///
+/// A pair of `u32` is packed into a 64-bit result as `(first as u64) << 32 | second as u64`. The
+/// low half is written into `a0` and the high half into `a1`:
+///
+/// ```nocompile
+/// #[define_env]
+/// pub mod some_env {
+/// 	fn range(&mut self, memory: &mut M) -> Result<(u32, u32), TrapReason> {
+/// 		// The guest reads the offset from `a1` and the length from `a0`.
+/// 		Ok((offset, len))
+/// 	}
+/// }
+/// ```
 ///
 /// The macro expands to `pub struct Env` declaration, with the following traits implementations:
 /// - `pallet_revive::wasm::Environment<Runtime<E>> where E: Ext`
@@ -87,6 +138,144 @@ use syn::{parse_quote, punctuated::Punctuated, spanned::Spanned, token::Comma, F
 /// The implementation on `()` can be used in places where no `Ext` exists, yet. This is useful
 /// when only checking whether a code can be instantiated without actually executing any code.
 ///
+/// A host function can be marked `#[deprecated]` or `#[removed]`. Removed host functions are
+/// not callable anymore but are still documented. Deprecated host functions stay callable but
+/// emit a warning to the log and the debug buffer the first time they are called within a call
+/// stack, as tracked by `Ext::note_deprecated_syscall`. `#[deprecated = "<note>"]` adds a note to
+/// the warning and the docs.
+/// All host functions are grouped by these attributes in the generated `syscalls_by_tier()`.
+///
+/// Host functions which write their results into guest memory should be annotated with
+/// `#[writes_memory]`. They are listed by the generated `syscalls_writing_memory()`. Likewise,
+/// host functions which are safe to call outside of a full transactional context are annotated
+/// with `#[context_safe]` and listed by `context_safe_syscalls()`. The size of a bounded output
+/// can be declared with `#[max_output(<u32>)]` and is returned by `syscall_max_output()`.
+///
+/// A host function which doesn't write to the guest memory can take `memory: &M`. Its body then
+/// only gets a shared reference. Host functions marked `#[writes_memory]` or returning an array
+/// must take `memory: &mut M`.
+///
+/// The base cost of a host function can be declared with `#[gas(<RuntimeCosts>)]`. It is charged
+/// before the arguments are decoded, so the body only needs to charge costs depending on them.
+/// The declared costs are also listed by the generated `syscalls_by_cost()`.
+///
+/// `#[alias("<ascii>")]` makes a host function additionally importable under the given symbol. It
+/// can be repeated and is useful to rename a host function while keeping the old symbol. Aliases
+/// share the attributes of the host function and are dispatched and listed like any other symbol.
+/// They are subject to the same restrictions as `#[symbol]`.
+///
+/// A `#[mutating]` host function fails with `Error::StateChangeDenied` when called in a read-only
+/// context. `#[mutating(error = <variant>)]` names another variant of the pallet's `Error`, while
+/// `#[mutating(error = <path>)]` returns an arbitrary error which converts into a `TrapReason`.
+/// The check runs before the `#[gas]` charge, so a read-only caller isn't charged for a call
+/// which is rejected anyway. The generated `bench_<symbol>` method skips this check so that
+/// benchmarks measure the body alone. The docs of every host function state whether it is
+/// `#[mutating]`.
+///
+/// Conversely, the current frame is marked read-only while a `#[read_only]` host function
+/// executes, so storage writes of its body fail with `Error::StateChangeDenied`. Other state
+/// changes aren't prevented. The attribute can't be combined with `#[mutating]`.
+///
+/// Host functions with an `#[api_version]` are part of the public interface and must have a doc
+/// comment. With the `deny-undocumented-syscalls` feature of this crate this is required for all
+/// host functions.
+///
+/// Host functions can be grouped by concern with a free-form `#[category = "<str>"]`. The
+/// category is only informational. It is shown in the docs and included in `SYSCALL_MANIFEST`.
+///
+/// `SYSCALL_ABI_HASH` is a hash over the names and signatures of all stable host functions.
+/// Contracts can be checked against it with `is_compatible_with()` or, for a detailed list of
+/// missing, changed and unstable syscalls, with `compatibility_report()`. `SYSCALL_MANIFEST`
+/// describes the same syscalls as a JSON array for tooling which doesn't want to parse Rust and
+/// `SYSCALL_TABLE_MD` as a markdown table.
+///
+/// A host function can carry multiple `#[cfg]` attributes which all have to hold. They are
+/// copied in declaration order to every item generated for it.
+///
+/// Host functions gated with `#[cfg(feature = "unstable-hostfn")]` are listed by
+/// `unstable_hostfn_syscalls()`, whether or not the feature is enabled.
+///
+/// All host function names are emitted sorted into `SYSCALL_NAMES`, including the ones disabled by
+/// `cfg`. A `SyscallId` is the position of a name in this table, so it doesn't depend on the
+/// enabled features. `intern()` looks up the id of a name with a binary search over this table.
+///
+/// The generated `Syscall` enum has one variant per host function, named after its PascalCased
+/// symbol. `Syscall::as_symbol()` and `Syscall::from_symbol()` convert between the two.
+///
+/// Host functions marked `#[bench_only]` are only used to set up state in benchmarks. They are
+/// neither dispatched, listed nor documented and only get the `bench_<symbol>` method.
+///
+/// With the `runtime-benchmarks` feature each host function which was not removed gets a
+/// `bench_<symbol>` method. Their symbols are listed in `BENCH_SYSCALLS` and `symbol_to_index()`
+/// returns the position of a symbol in that list. `REQUIRED_COSTS` lists the symbols of all
+/// dispatched host functions except aliases, so a test can check that each of them was benchmarked.
+///
+/// For unit tests each callable host function gets a `mock_<symbol>(ext, memory)` function. It
+/// calls the host function on a fresh `Runtime` with the registers and memory of `memory`, so a
+/// single host function can be tested without executing a contract. The pallet passes its
+/// `MockMemory` together with the `Ext` of a mocked call stack.
+///
+/// `resolve_syscall_id()` maps an import symbol to its `SyscallId` and
+/// `Runtime::handle_ecall_by_id()` dispatches on it. The pallet resolves the imports of a module
+/// once when preparing a call, so no symbol is compared on each call. `Runtime::handle_ecall()`
+/// resolves the symbol and then dispatches on its id. There can be at most `u16::MAX` syscalls.
+///
+/// Instead of taking an output pointer a host function can return `Result<[u8; N], TrapReason>`.
+/// Its last argument must then be `out_ptr: u32` and the generated code writes the returned array
+/// to the guest memory at `out_ptr`. Such host functions are considered `#[writes_memory]`.
+///
+/// A `u128` argument doesn't fit into a register. The guest passes a pointer to its little endian
+/// encoding instead, unless all arguments are passed in memory anyway. The same holds for a
+/// `[u8; N]` argument, whose bytes are read with `Memory::read_array`.
+///
+/// `#[slice(ptr = <name>_ptr, len = <name>_len)]` names two `u32` arguments which point to a byte
+/// slice in guest memory. The slice is read before the body runs and bound to `<name>: &[u8]`.
+/// The generated docs show that binding in place of the two arguments. As the length is chosen
+/// by the guest, `RuntimeCosts::CopyFromContract(<name>_len)` is charged right before the read,
+/// so the body must not charge for the copy again. In order, a call charges `#[gas]`, decodes the
+/// arguments, charges and reads each slice and then runs the body.
+///
+/// Arguments listed in `#[no_trace(<arg>, ..)]` are printed as `<redacted>` when tracing calls.
+/// Tracing is only generated with the `contract-host-trace` feature of this crate, which is
+/// enabled by default. A call to an unknown syscall appends its symbol to the debug buffer before
+/// trapping, as long as the `runtime::revive::strace` target is enabled.
+/// With the `structured-strace` feature calls are not formatted into the debug buffer but passed
+/// as name, argument and result records to `Ext::trace_syscall`.
+///
+/// By default every host function body is inlined into `Runtime::handle_ecall`. With the
+/// `syscall-wrappers` feature each body is lifted into an `#[inline(always)]` method named
+/// `syscall_<symbol>` instead, which is called by the dispatch code. Profiles then attribute time
+/// to the individual host functions.
+///
+/// With the `host-timing` feature the wall clock time spent in each host function body is passed
+/// to `Ext::record_host_time` together with the import symbol. It requires `std`.
+///
+/// Independent of the feature set the macro emits `list_syscalls`, `list_syscalls_with_versions`,
+/// `is_stable_syscall`, `SYSCALL_COUNT`, `STABLE_SYSCALL_COUNT`, `syscall_arity`,
+/// `expected_signature` and `syscall_max_output`. With the `verifier-only` feature of this crate
+/// enabled, those are the only items emitted. This is meant for light-weight verifiers of contract
+/// imports.
+///
+/// `#[define_env(docs_only)]` additionally emits `SyscallDoc`, `SYSCALL_MANIFEST`,
+/// `SYSCALL_TABLE_MD` and the syscall lists like `syscalls_by_tier()`, but no dispatch code. It is
+/// meant for crates which only re-export the interface for documentation or client generation.
+///
+/// Host function arguments can be signed or unsigned integers of up to 64 bits. 64 bit arguments
+/// occupy two registers. If more than six registers would be needed, all arguments are read from
+/// the memory pointed to by the first register instead. A lower limit can be set per host
+/// function with `#[max_regs(<u32>)]`.
+///
+/// Arguments of type `u8`, `u16`, `i8` and `i16` are silently truncated from their register. With
+/// the `strict-args` feature out of range values trap with `Error::DecodingFailed` instead.
+///
+/// Syscalls whose name is longer than 32 bytes are listed in the `#[cfg(test)]` constant
+/// `OVERLONG_SYSCALLS` so that a test can reject them. The limit can be changed with
+/// `#[define_env(max_symbol_len = <usize>)]`.
+///
+/// Every call is charged `RuntimeCosts::HostFn` before the host function is entered. Runtimes
+/// which calibrate this overhead differently can substitute the expression with
+/// `#[define_env(base_cost = <RuntimeCosts>)]`.
+///
 ///
 /// To build up these docs, run:
 ///
@@ -95,55 +284,230 @@ use syn::{parse_quote, punctuated::Punctuated, spanned::Spanned, token::Comma, F
 /// ```
 #[proc_macro_attribute]
 pub fn define_env(attr: TokenStream, item: TokenStream) -> TokenStream {
-	if !attr.is_empty() {
-		let msg = r#"Invalid `define_env` attribute macro: expected no attributes:
-					 - `#[define_env]`"#;
-		let span = TokenStream2::from(attr).span();
-		return syn::Error::new(span, msg).to_compile_error().into()
-	}
-
+	let config = syn::parse_macro_input!(attr as EnvConfig);
 	let item = syn::parse_macro_input!(item as syn::ItemMod);
 
 	match EnvDef::try_from(item) {
-		Ok(mut def) => expand_env(&mut def).into(),
+		Ok(def) => expand_env(&def, &config).into(),
 		Err(e) => e.to_compile_error().into(),
 	}
 }
 
+/// Import symbols longer than this are reported unless overridden via `max_symbol_len`.
+const DEFAULT_MAX_SYMBOL_LEN: usize = 32;
+
+/// Options passed to the `#[define_env]` attribute.
+struct EnvConfig {
+	/// Symbols longer than this amount of bytes are listed in `OVERLONG_SYSCALLS`.
+	max_symbol_len: usize,
+	/// The overhead charged for every host function call.
+	base_cost: syn::Expr,
+	/// Only emit the syscall metadata functions (`verifier-only` feature).
+	verifier_only: bool,
+	/// Only emit the documentation and the syscall lists but no dispatch code.
+	docs_only: bool,
+	/// Trap on out of range narrow integer arguments (`strict-args` feature).
+	strict_args: bool,
+	/// Trace every host function call (`contract-host-trace` feature).
+	host_trace: bool,
+	/// Pass traces to `Ext::trace_syscall` instead of formatting them (`structured-strace`
+	/// feature).
+	structured_trace: bool,
+	/// Require docs on unstable host functions, too (`deny-undocumented-syscalls` feature).
+	deny_undocumented: bool,
+	/// Lift each host function body into its own method (`syscall-wrappers` feature).
+	syscall_wrappers: bool,
+	/// Pass the time spent in each body to `Ext::record_host_time` (`host-timing` feature).
+	host_timing: bool,
+}
+
+/// The configuration of the default feature set regardless of the features this crate is built
+/// with, so that the unit tests don't depend on them.
+impl Default for EnvConfig {
+	fn default() -> Self {
+		Self {
+			max_symbol_len: DEFAULT_MAX_SYMBOL_LEN,
+			base_cost: parse_quote! { crate::wasm::RuntimeCosts::HostFn },
+			verifier_only: false,
+			docs_only: false,
+			strict_args: false,
+			host_trace: true,
+			structured_trace: false,
+			deny_undocumented: false,
+			syscall_wrappers: false,
+			host_timing: false,
+		}
+	}
+}
+
+impl EnvConfig {
+	/// The configuration selected by the features this crate is built with.
+	fn from_features() -> Self {
+		Self {
+			verifier_only: cfg!(feature = "verifier-only"),
+			strict_args: cfg!(feature = "strict-args"),
+			host_trace: cfg!(feature = "contract-host-trace"),
+			structured_trace: cfg!(feature = "structured-strace"),
+			deny_undocumented: cfg!(feature = "deny-undocumented-syscalls"),
+			syscall_wrappers: cfg!(feature = "syscall-wrappers"),
+			host_timing: cfg!(feature = "host-timing"),
+			..Self::default()
+		}
+	}
+}
+
+impl syn::parse::Parse for EnvConfig {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let msg = r#"Invalid `define_env` attribute macro: expected one of:
+					 - `#[define_env]`
+					 - `#[define_env(max_symbol_len = <usize>)]`
+					 - `#[define_env(base_cost = <RuntimeCosts>)]`
+					 - `#[define_env(docs_only)]`"#;
+		let mut config = Self::from_features();
+		for meta in Punctuated::<syn::Meta, Comma>::parse_terminated(input)? {
+			match meta {
+				syn::Meta::NameValue(nv) if nv.path.is_ident("max_symbol_len") => {
+					let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. }) = nv.value
+					else {
+						return Err(syn::Error::new(nv.value.span(), msg))
+					};
+					config.max_symbol_len = lit.base10_parse()?;
+				},
+				syn::Meta::NameValue(nv) if nv.path.is_ident("base_cost") => {
+					config.base_cost = nv.value;
+				},
+				syn::Meta::Path(path) if path.is_ident("docs_only") => {
+					config.docs_only = true;
+				},
+				meta => return Err(syn::Error::new(meta.span(), msg)),
+			}
+		}
+		Ok(config)
+	}
+}
+
 /// Parsed environment definition.
 struct EnvDef {
 	host_funcs: Vec<HostFn>,
+	/// Host functions marked `#[bench_only]` which only get a `bench_*` function.
+	bench_only: Vec<HostFn>,
 }
 
 /// Parsed host function definition.
+#[derive(Clone)]
 struct HostFn {
 	item: syn::ItemFn,
 	api_version: Option<u16>,
 	name: String,
+	version: u8,
 	returns: HostFnReturn,
-	cfg: Option<syn::Attribute>,
+	/// All `#[cfg]` attributes in declaration order. They have to hold all at once.
+	cfg: Vec<syn::Attribute>,
+	/// The error returned when a `#[mutating]` host function is called in a read-only context.
+	mutating: Option<syn::Path>,
+	/// Whether the contract is treated as read-only while the body executes.
+	read_only: bool,
+	/// Whether the memory is taken as `&mut M` rather than `&M`.
+	memory_mut: bool,
+	writes_memory: bool,
+	context_safe: bool,
+	max_output: Option<u32>,
+	gas: Option<syn::Expr>,
+	max_regs: u32,
+	deprecated: Option<String>,
+	removed: bool,
+	no_trace: Vec<Ident>,
+	/// Byte slices passed as a pointer and length pair declared with `#[slice]`.
+	slices: Vec<SliceArg>,
+	category: Option<String>,
+	bench_only: bool,
+	/// Additional symbols declared with `#[alias]`.
+	aliases: Vec<String>,
+	/// The symbol of the host function this one is an alias of.
+	alias_of: Option<String>,
+}
+
+/// A byte slice passed by the guest as a pointer and a length argument.
+#[derive(Clone)]
+struct SliceArg {
+	/// The name of the `&[u8]` binding available to the body.
+	name: Ident,
+	ptr: Ident,
+	len: Ident,
+}
+
+/// Lifecycle stage of a host function as derived from its attributes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Tier {
+	/// Versioned via `#[api_version]`.
+	Stable,
+	/// Marked `#[deprecated]`; still callable.
+	Deprecated,
+	/// Without `#[api_version]`; only callable with `ApiVersion::UnsafeNewest`.
+	Unstable,
+	/// Marked `#[removed]`; kept for reference only and not callable anymore.
+	Removed,
 }
 
+#[derive(Clone)]
 enum HostFnReturn {
 	Unit,
 	U32,
+	I32,
+	/// Written into the output register as is; the guest reads the low half from `a0` and the
+	/// high half from `a1`.
+	U64,
+	I64,
 	ReturnCode,
+	/// A syscall specific error code enum which converts into `ReturnErrorCode`.
+	ReturnCodeEnum(Box<syn::Type>),
+	/// A `[u8; N]` which is written to the guest memory at `out_ptr`.
+	Array(usize),
+	/// Two `u32` packed into the 64-bit output with the first one in the high half.
+	U32Pair,
 }
 
 impl HostFnReturn {
 	fn map_output(&self) -> TokenStream2 {
 		match self {
-			Self::Unit => quote! { |_| None },
-			Self::U32 => quote! { |ret_val| Some(ret_val) },
-			Self::ReturnCode => quote! { |ret_code| Some(ret_code.into())  },
+			Self::Unit | Self::Array(_) => quote! { |_| None },
+			Self::U32 => quote! { |ret_val| Some(ret_val.into()) },
+			// Same width cast which keeps the two's complement bit pattern.
+			Self::I32 => quote! { |ret_val| Some(u64::from(ret_val as u32)) },
+			Self::U64 => quote! { |ret_val| Some(ret_val) },
+			Self::I64 => quote! { |ret_val| Some(ret_val as u64) },
+			Self::ReturnCode => quote! { |ret_code| Some(u32::from(ret_code).into()) },
+			Self::ReturnCodeEnum(ty) => quote! {
+				|ret_code: #ty| {
+					Some(u32::from(<#ty as Into<ReturnErrorCode>>::into(ret_code)).into())
+				}
+			},
+			Self::U32Pair => quote! {
+				|(first, second): (u32, u32)| Some(u64::from(first) << 32 | u64::from(second))
+			},
 		}
 	}
 
 	fn success_type(&self) -> syn::ReturnType {
 		match self {
-			Self::Unit => syn::ReturnType::Default,
+			Self::Unit | Self::Array(_) => syn::ReturnType::Default,
 			Self::U32 => parse_quote! { -> u32 },
-			Self::ReturnCode => parse_quote! { -> ReturnErrorCode },
+			Self::I32 => parse_quote! { -> i32 },
+			Self::U64 => parse_quote! { -> u64 },
+			Self::I64 => parse_quote! { -> i64 },
+			Self::ReturnCode | Self::ReturnCodeEnum(_) => parse_quote! { -> ReturnErrorCode },
+			Self::U32Pair => parse_quote! { -> (u32, u32) },
+		}
+	}
+
+	/// The type of the output register as seen by the guest.
+	fn guest_type(&self) -> &'static str {
+		match self {
+			Self::Unit | Self::Array(_) => "()",
+			Self::U32 | Self::ReturnCode | Self::ReturnCodeEnum(_) => "u32",
+			Self::I32 => "i32",
+			Self::U64 | Self::U32Pair => "u64",
+			Self::I64 => "i64",
 		}
 	}
 }
@@ -168,8 +532,153 @@ impl EnvDef {
 			.filter_map(extract_fn)
 			.map(HostFn::try_from)
 			.collect::<Result<Vec<_>, _>>()?;
+		// Every alias is dispatched and listed like a host function of its own.
+		let host_funcs = host_funcs
+			.into_iter()
+			.flat_map(|f| {
+				let aliases = f.aliases.iter().map(|alias| HostFn {
+					name: alias.clone(),
+					aliases: Vec::new(),
+					alias_of: Some(f.name.clone()),
+					..f.clone()
+				});
+				let aliases = aliases.collect::<Vec<_>>();
+				core::iter::once(f).chain(aliases)
+			})
+			.collect::<Vec<_>>();
+
+		// Functions of the same name are fine as long as their `cfg` differ.
+		let cfg = |f: &HostFn| {
+			f.cfg.iter().map(|cfg| cfg.to_token_stream().to_string()).collect::<Vec<_>>()
+		};
+		for (idx, f) in host_funcs.iter().enumerate() {
+			let duplicate = host_funcs[..idx].iter().any(|prev| {
+				prev.name == f.name &&
+					(prev.cfg.is_empty() || f.cfg.is_empty() || cfg(prev) == cfg(f))
+			});
+			if duplicate {
+				let msg = format!("Duplicate syscall `{}`", f.name);
+				return Err(syn::Error::new(f.item.sig.ident.span(), msg))
+			}
+		}
+
+		let (bench_only, host_funcs) = host_funcs.into_iter().partition(|f| f.bench_only);
+		let def = Self { host_funcs, bench_only };
+		def.check_syscall_count().map_err(err)?;
+		Ok(def)
+	}
 
-		Ok(Self { host_funcs })
+	/// Fails if there are too many syscalls for each [`Self::syscall_id`] to fit into `u16`.
+	fn check_syscall_count(&self) -> Result<(), &'static str> {
+		if self.sorted_names().len() > usize::from(u16::MAX) {
+			return Err("Too many syscalls: a `SyscallId` has to fit into `u16`")
+		}
+		Ok(())
+	}
+
+	/// A slice expression of the import symbols of all host functions matching `filter`.
+	fn symbols(&self, filter: impl Fn(&HostFn) -> bool) -> TokenStream2 {
+		let symbols = self.host_funcs.iter().filter(|f| filter(f)).map(|f| {
+			let cfg = &f.cfg;
+			let symbol = Literal::byte_string(f.name.as_bytes());
+			quote! { #( #cfg )* #symbol }
+		});
+		quote! { &[#( #symbols ),*] }
+	}
+
+	/// The names of all host functions in lexicographic order, each gated by its `cfg`.
+	fn sorted_names(&self) -> Vec<&str> {
+		let mut names = self.host_funcs.iter().map(|f| f.name.as_str()).collect::<Vec<_>>();
+		names.sort();
+		names.dedup();
+		names
+	}
+
+	/// The id of the syscall with the given export symbol.
+	///
+	/// This is the position of the symbol in [`Self::sorted_names`]. It does not depend on which
+	/// host functions are enabled by `cfg`.
+	fn syscall_id(&self, name: &str) -> u16 {
+		let id = self
+			.sorted_names()
+			.binary_search(&name)
+			.expect("name belongs to a host function");
+		u16::try_from(id).expect("the number of syscalls is checked in `EnvDef::try_from`")
+	}
+
+	/// Hash over the names and signatures of all stable host functions not gated by `cfg`.
+	fn abi_hash(&self) -> [u8; 32] {
+		let mut funcs = self
+			.host_funcs
+			.iter()
+			.filter(|f| f.cfg.is_empty() && f.is_callable(false))
+			.collect::<Vec<_>>();
+		funcs.sort_by(|a, b| a.name.cmp(&b.name));
+		let mut hasher = Blake2b::<U32>::new();
+		for f in funcs {
+			hasher.update(format!("{}:{}\n", f.name, f.signature()));
+		}
+		hasher.finalize().into()
+	}
+
+	/// All host functions which get a `bench_*` function, including the `#[bench_only]` ones.
+	fn bench_funcs(&self) -> impl Iterator<Item = &HostFn> {
+		self.host_funcs.iter().chain(&self.bench_only).filter(|f| f.has_bench())
+	}
+
+	/// A JSON array describing all callable host functions not gated by `cfg`.
+	fn manifest(&self) -> String {
+		let entries = self
+			.host_funcs
+			.iter()
+			.filter(|f| f.cfg.is_empty() && f.is_callable(true))
+			.map(|f| {
+				let args = f.param_types();
+				let args = args.iter().map(|ty| format!("\"{ty}\"")).collect::<Vec<_>>();
+				let category = f.category.as_ref().map_or_else(String::new, |category| {
+					let category = category.replace('\\', "\\\\").replace('"', "\\\"");
+					format!(r#","category":"{category}""#)
+				});
+				format!(
+					r#"{{"name":"{}","stable":{},"arity":{},"args":[{}],"returns":"{}"{}}}"#,
+					f.name,
+					f.api_version.is_some(),
+					args.len(),
+					args.join(","),
+					f.returns.guest_type(),
+					category,
+				)
+			})
+			.collect::<Vec<_>>();
+		format!("[{}]", entries.join(","))
+	}
+
+	/// A markdown table listing the same syscalls as [`Self::manifest`].
+	fn markdown_table(&self) -> String {
+		let mut table = String::from("| name | args | return | stable |\n|---|---|---|---|\n");
+		for f in self.host_funcs.iter().filter(|f| f.cfg.is_empty() && f.is_callable(true)) {
+			let args = f
+				.params()
+				.map(|p| format!("`{}: {}`", p.pat.to_token_stream(), p.ty.to_token_stream()))
+				.collect::<Vec<_>>();
+			table.push_str(&format!(
+				"| `{}` | {} | `{}` | {} |\n",
+				f.name,
+				args.join(", "),
+				f.returns.guest_type(),
+				if f.api_version.is_some() { "yes" } else { "no" },
+			));
+		}
+		table
+	}
+
+	/// Names of all host functions whose import symbol is longer than `max_len` bytes.
+	fn overlong_symbols(&self, max_len: usize) -> Vec<&str> {
+		self.host_funcs
+			.iter()
+			.filter(|f| f.name.len() > max_len)
+			.map(|f| f.name.as_str())
+			.collect()
 	}
 }
 
@@ -181,13 +690,34 @@ impl HostFn {
 		};
 
 		// process attributes
-		let msg = "Only #[api_version(<u16>)], #[cfg] and #[mutating] attributes are allowed.";
+		let msg = "Only #[api_version(<u16>)], #[cfg], #[mutating(error = <path>)], #[read_only], \
+			#[writes_memory], \
+			#[context_safe], #[max_output(<u32>)], #[gas(<RuntimeCosts>)], #[max_regs(<u32>)], \
+			#[version(<u8>)], #[symbol = <str>], #[alias(<str>)], #[category = <str>], #[deprecated], \
+			#[removed], #[bench_only], #[slice(ptr = <arg>, len = <arg>)] and \
+			#[no_trace(<arg>, ..)] attributes are \
+			allowed.";
 		let span = item.span();
 		let mut attrs = item.attrs.clone();
 		attrs.retain(|a| !a.path().is_ident("doc"));
 		let mut api_version = None;
-		let mut mutating = false;
-		let mut cfg = None;
+		let mut mutating = None;
+		let mut read_only = false;
+		let mut writes_memory = false;
+		let mut context_safe = false;
+		let mut max_output = None;
+		let mut gas = None;
+		let mut max_regs = None;
+		let mut version = None;
+		let mut symbol = None;
+		let mut cfg = Vec::new();
+		let mut deprecated = None;
+		let mut removed = false;
+		let mut bench_only = false;
+		let mut no_trace = Vec::new();
+		let mut slices = Vec::new();
+		let mut category = None;
+		let mut aliases = Vec::new();
 		while let Some(attr) = attrs.pop() {
 			let ident = attr.path().get_ident().ok_or(err(span, msg))?.to_string();
 			match ident.as_str() {
@@ -199,33 +729,231 @@ impl HostFn {
 						Some(attr.parse_args::<syn::LitInt>().and_then(|lit| lit.base10_parse())?);
 				},
 				"mutating" => {
-					if mutating {
+					if mutating.is_some() {
 						return Err(err(span, "#[mutating] can only be specified once"))
 					}
-					mutating = true;
+					mutating = Some(match &attr.meta {
+						syn::Meta::Path(_) => parse_quote! { StateChangeDenied },
+						syn::Meta::List(_) => {
+							let meta = attr.parse_args::<syn::MetaNameValue>()?;
+							if !meta.path.is_ident("error") {
+								return Err(err(
+									meta.path.span(),
+									"Expected #[mutating(error = <path>)]",
+								))
+							}
+							match meta.value {
+								syn::Expr::Path(error) => error.path,
+								value => return Err(err(value.span(), "Expected a path")),
+							}
+						},
+						syn::Meta::NameValue(_) => return Err(err(attr.span(), msg)),
+					});
+				},
+				"writes_memory" => {
+					if writes_memory {
+						return Err(err(span, "#[writes_memory] can only be specified once"))
+					}
+					writes_memory = true;
+				},
+				"context_safe" => {
+					if context_safe {
+						return Err(err(span, "#[context_safe] can only be specified once"))
+					}
+					context_safe = true;
+				},
+				"max_output" => {
+					if max_output.is_some() {
+						return Err(err(span, "#[max_output] can only be specified once"))
+					}
+					max_output =
+						Some(attr.parse_args::<syn::LitInt>().and_then(|lit| lit.base10_parse())?);
+				},
+				"gas" => {
+					if gas.is_some() {
+						return Err(err(span, "#[gas] can only be specified once"))
+					}
+					gas = Some(attr.parse_args::<syn::Expr>()?);
+				},
+				"max_regs" => {
+					if max_regs.is_some() {
+						return Err(err(span, "#[max_regs] can only be specified once"))
+					}
+					let lit = attr.parse_args::<syn::LitInt>()?;
+					let value = lit.base10_parse()?;
+					if value > ALLOWED_REGISTERS {
+						let msg = format!("#[max_regs] can be at most {ALLOWED_REGISTERS}");
+						return Err(err(lit.span(), &msg))
+					}
+					max_regs = Some(value);
+				},
+				"version" => {
+					if version.is_some() {
+						return Err(err(attr.span(), "#[version] can only be specified once"))
+					}
+					version =
+						Some(attr.parse_args::<syn::LitInt>().and_then(|lit| lit.base10_parse())?);
+				},
+				"symbol" => {
+					if symbol.is_some() {
+						return Err(err(attr.span(), "#[symbol] can only be specified once"))
+					}
+					let value = &attr.meta.require_name_value()?.value;
+					let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) = value else {
+						return Err(err(value.span(), "Expected a string literal"))
+					};
+					let value = lit.value();
+					if value.is_empty() || !value.is_ascii() {
+						return Err(err(lit.span(), "#[symbol] must be a non-empty ASCII string"))
+					}
+					if syn::parse_str::<Ident>(&symbol_ident(&value)).is_err() {
+						return Err(err(
+							lit.span(),
+							"#[symbol] must not start with a digit or be a Rust keyword",
+						))
+					}
+					symbol = Some(value);
+				},
+				"alias" => {
+					let lit = attr.parse_args::<syn::LitStr>()?;
+					let value = lit.value();
+					if value.is_empty() || !value.is_ascii() {
+						return Err(err(lit.span(), "#[alias] must be a non-empty ASCII string"))
+					}
+					if syn::parse_str::<Ident>(&symbol_ident(&value)).is_err() {
+						return Err(err(
+							lit.span(),
+							"#[alias] must not start with a digit or be a Rust keyword",
+						))
+					}
+					aliases.push(value);
+				},
+				"category" => {
+					if category.is_some() {
+						return Err(err(attr.span(), "#[category] can only be specified once"))
+					}
+					let value = &attr.meta.require_name_value()?.value;
+					let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) = value else {
+						return Err(err(value.span(), "Expected a string literal"))
+					};
+					category = Some(lit.value());
 				},
-				"cfg" => {
-					if cfg.is_some() {
-						return Err(err(span, "#[cfg] can only be specified once"))
+				"cfg" => cfg.push(attr),
+				"deprecated" => {
+					if deprecated.is_some() {
+						return Err(err(span, "#[deprecated] can only be specified once"))
 					}
-					cfg = Some(attr);
+					deprecated = Some(match &attr.meta {
+						syn::Meta::Path(_) => String::new(),
+						syn::Meta::NameValue(meta) => match &meta.value {
+							syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(note), .. }) =>
+								note.value(),
+							value => return Err(err(value.span(), "Expected a string literal")),
+						},
+						syn::Meta::List(_) => return Err(err(attr.span(), msg)),
+					});
+				},
+				"read_only" => {
+					if read_only {
+						return Err(err(span, "#[read_only] can only be specified once"))
+					}
+					read_only = true;
+				},
+				"removed" => {
+					if removed {
+						return Err(err(span, "#[removed] can only be specified once"))
+					}
+					removed = true;
+				},
+				"bench_only" => {
+					if bench_only {
+						return Err(err(span, "#[bench_only] can only be specified once"))
+					}
+					bench_only = true;
+				},
+				"no_trace" => {
+					no_trace.extend(
+						attr.parse_args_with(Punctuated::<Ident, Comma>::parse_terminated)?,
+					);
+				},
+				"slice" => {
+					let args = attr.parse_args_with(
+						Punctuated::<syn::MetaNameValue, Comma>::parse_terminated,
+					)?;
+					let arg = |name| {
+						let value = args.iter().find(|arg| arg.path.is_ident(name)).map(|arg| {
+							match &arg.value {
+								syn::Expr::Path(path) => path.path.get_ident().cloned(),
+								_ => None,
+							}
+						});
+						value.flatten().ok_or_else(|| {
+							err(attr.span(), "Expected #[slice(ptr = <arg>, len = <arg>)]")
+						})
+					};
+					let (ptr, len) = (arg("ptr")?, arg("len")?);
+					if args.len() != 2 {
+						return Err(err(attr.span(), "Expected #[slice(ptr = <arg>, len = <arg>)]"))
+					}
+					let Some(name) = ptr
+						.to_string()
+						.strip_suffix("_ptr")
+						.map(|name| Ident::new(name, ptr.span()))
+					else {
+						return Err(err(ptr.span(), "#[slice] pointer must be named `<name>_ptr`"))
+					};
+					slices.push(SliceArg { name, ptr, len });
 				},
 				id => return Err(err(span, &format!("Unsupported attribute \"{id}\". {msg}"))),
 			}
 		}
 
-		if mutating {
-			let stmt = syn::parse_quote! {
-				if self.ext().is_read_only() {
-					return Err(Error::<E::T>::StateChangeDenied.into());
-				}
+		// Attributes are processed in reverse.
+		aliases.reverse();
+		cfg.reverse();
+		slices.reverse();
+
+		for arg in slices.iter().flat_map(|slice| [&slice.ptr, &slice.len]) {
+			let is_u32 = |p: &FnArg| {
+				is_arg(p, arg) &&
+					matches!(p, FnArg::Typed(pat) if pat.ty.to_token_stream().to_string() == "u32")
 			};
-			item.block.stmts.insert(0, stmt);
+			if !item.sig.inputs.iter().skip(2).any(is_u32) {
+				return Err(err(arg.span(), "#[slice] refers to an unknown `u32` argument"))
+			}
+		}
+		// The slices are read before the body runs and shadow the owned buffer by a `&[u8]`. The
+		// length is chosen by the guest, so the copy is charged before anything is allocated.
+		let reads = slices.iter().flat_map(|SliceArg { name, ptr, len }| -> [syn::Stmt; 3] {
+			[
+				parse_quote! {
+					self.charge_gas(crate::wasm::RuntimeCosts::CopyFromContract(#len))?;
+				},
+				parse_quote! { let #name = memory.read(#ptr, #len)?; },
+				parse_quote! { let #name: &[u8] = &#name; },
+			]
+		});
+		item.block.stmts.splice(0..0, reads.collect::<Vec<_>>());
+
+		if let Some(error) = &mutating {
+			if read_only {
+				return Err(err(span, "#[read_only] and #[mutating] are mutually exclusive"))
+			}
+			item.block.stmts.insert(0, read_only_guard(error));
+		}
+		if read_only {
+			item.block = read_only_block(&item.block, &item.sig.output);
 		}
 
-		let name = item.sig.ident.to_string();
+		let version = version.unwrap_or_default();
+		let name = match (symbol, version) {
+			(Some(symbol), _) => symbol,
+			(None, 0) => item.sig.ident.to_string(),
+			(None, version) => format!("seal{version}_{}", item.sig.ident),
+		};
 
-		let msg = "Every function must start with these two parameters: &mut self, memory: &mut M";
+		let msg = "Every function must start with these two parameters: &mut self, memory: &mut M \
+			(or memory: &M)";
 		let special_args = item
 			.sig
 			.inputs
@@ -238,12 +966,29 @@ impl HostFn {
 		if special_args != 2 {
 			return Err(err(span, msg))
 		}
+		let memory_mut = matches!(
+			&item.sig.inputs[1],
+			FnArg::Typed(pat) if matches!(&*pat.ty, syn::Type::Reference(r) if r.mutability.is_some())
+		);
+
+		if let Some(unknown) = no_trace
+			.iter()
+			.find(|arg| !item.sig.inputs.iter().skip(2).any(|p| is_arg(p, arg)))
+		{
+			return Err(err(unknown.span(), "#[no_trace] refers to an unknown argument"))
+		}
 
 		// process return type
 		let msg = r#"Should return one of the following:
 				- Result<(), TrapReason>,
 				- Result<ReturnErrorCode, TrapReason>,
-				- Result<u32, TrapReason>"#;
+				- Result<E, TrapReason> where E is an enum implementing Into<ReturnErrorCode>,
+				- Result<u32, TrapReason>,
+				- Result<i32, TrapReason>,
+				- Result<u64, TrapReason>,
+				- Result<i64, TrapReason>,
+				- Result<(u32, u32), TrapReason>,
+				- Result<[u8; N], TrapReason>"#;
 		let ret_ty = match item.clone().sig.output {
 			syn::ReturnType::Type(_, ty) => Ok(ty.clone()),
 			_ => Err(err(span, &msg)),
@@ -286,30 +1031,87 @@ impl HostFn {
 							syn::GenericArgument::Type(ty) => Ok(ty.clone()),
 							_ => Err(err(arg1.span(), &msg)),
 						}?;
-						let ok_ty_str = match ok_ty {
-							syn::Type::Path(tp) => Ok(tp
-								.path
-								.segments
-								.first()
-								.ok_or(err(arg1.span(), &msg))?
-								.ident
-								.to_string()),
-							syn::Type::Tuple(tt) => {
-								if !tt.elems.is_empty() {
-									return Err(err(arg1.span(), &msg))
-								};
-								Ok("()".to_string())
-							},
-							_ => Err(err(ok_ty.span(), &msg)),
-						}?;
-						let returns = match ok_ty_str.as_str() {
-							"()" => Ok(HostFnReturn::Unit),
-							"u32" => Ok(HostFnReturn::U32),
-							"ReturnErrorCode" => Ok(HostFnReturn::ReturnCode),
-							_ => Err(err(arg1.span(), &msg)),
-						}?;
+						let returns = if let syn::Type::Array(array) = &ok_ty {
+							let is_u8 = matches!(&*array.elem, syn::Type::Path(tp) if tp.path.is_ident("u8"));
+							let len = match &array.len {
+								syn::Expr::Lit(syn::ExprLit {
+									lit: syn::Lit::Int(lit), ..
+								}) if is_u8 => lit.base10_parse::<usize>()?,
+								_ => return Err(err(array.span(), msg)),
+							};
+							let has_out_ptr = match item.sig.inputs.iter().skip(2).last() {
+								Some(arg @ FnArg::Typed(pat)) =>
+									is_arg(arg, &parse_quote!(out_ptr)) &&
+										pat.ty.to_token_stream().to_string() == "u32",
+								_ => false,
+							};
+							if !has_out_ptr {
+								let msg = "Host functions returning an array must take \
+									`out_ptr: u32` as their last argument";
+								return Err(err(item.sig.inputs.span(), msg))
+							}
 
-						Ok(Self { item, api_version, name, returns, cfg })
+							HostFnReturn::Array(len)
+						} else {
+							let is_u32 = |ty: &syn::Type| match ty {
+								syn::Type::Path(tp) => tp.path.is_ident("u32"),
+								_ => false,
+							};
+							let is = |ident: &str| match &ok_ty {
+								syn::Type::Path(tp) =>
+									tp.qself.is_none() && tp.path.is_ident(ident),
+								_ => false,
+							};
+							match &ok_ty {
+								syn::Type::Tuple(tt) if tt.elems.is_empty() =>
+									Ok(HostFnReturn::Unit),
+								syn::Type::Tuple(tt)
+									if tt.elems.len() == 2 && tt.elems.iter().all(is_u32) =>
+									Ok(HostFnReturn::U32Pair),
+								_ if is("u32") => Ok(HostFnReturn::U32),
+								_ if is("i32") => Ok(HostFnReturn::I32),
+								_ if is("u64") => Ok(HostFnReturn::U64),
+								_ if is("i64") => Ok(HostFnReturn::I64),
+								_ if is("ReturnErrorCode") => Ok(HostFnReturn::ReturnCode),
+								// Whether the enum converts into `ReturnErrorCode` is checked by
+								// the compiler.
+								syn::Type::Path(tp) if is_error_code_enum(tp) =>
+									Ok(HostFnReturn::ReturnCodeEnum(Box::new(ok_ty.clone()))),
+								_ => Err(err(ok_ty.span(), &msg)),
+							}?
+						};
+						// The array is written to guest memory by the generated code.
+						let writes_memory =
+							writes_memory || matches!(returns, HostFnReturn::Array(_));
+						if writes_memory && !memory_mut {
+							let msg = "Host functions writing memory must take `memory: &mut M`";
+							return Err(err(item.sig.inputs.span(), msg))
+						}
+
+						Ok(Self {
+							item,
+							api_version,
+							name,
+							version,
+							returns,
+							cfg,
+							mutating,
+							read_only,
+							memory_mut,
+							writes_memory,
+							context_safe,
+							max_output,
+							gas,
+							max_regs: max_regs.unwrap_or(ALLOWED_REGISTERS),
+							deprecated,
+							removed,
+							bench_only,
+							no_trace,
+							slices,
+							category,
+							aliases,
+							alias_of: None,
+						})
 					},
 					_ => Err(err(span, &msg)),
 				}
@@ -319,6 +1121,268 @@ impl HostFn {
 	}
 }
 
+impl HostFn {
+	/// Name and value of each argument as passed to `Ext::trace_syscall`.
+	///
+	/// Arguments listed in `#[no_trace]` are passed as `<redacted>` instead of their value.
+	fn trace_args(&self) -> Vec<TokenStream2> {
+		self.params()
+			.filter_map(|p| match &*p.pat {
+				syn::Pat::Ident(pat_ident) => Some(pat_ident.ident.clone()),
+				_ => None,
+			})
+			.map(|arg| {
+				let name = arg.to_string();
+				if self.no_trace.contains(&arg) {
+					quote! { (#name, &format_args!("<redacted>") as &dyn core::fmt::Debug) }
+				} else {
+					quote! { (#name, &#arg as &dyn core::fmt::Debug) }
+				}
+			})
+			.collect()
+	}
+
+	/// Format string and arguments used to trace a call to this host function.
+	///
+	/// Arguments listed in `#[no_trace]` are printed as `<redacted>` instead of their value. Braces
+	/// in the symbol are escaped so that they aren't taken as placeholders.
+	fn trace_fmt(&self) -> (String, Vec<Ident>) {
+		let params = self.item.sig.inputs.iter().skip(2).filter_map(|arg| match arg {
+			syn::FnArg::Receiver(_) => None,
+			syn::FnArg::Typed(p) => match *p.pat.clone() {
+				syn::Pat::Ident(ref pat_ident) => Some(pat_ident.ident.clone()),
+				_ => None,
+			},
+		});
+
+		let mut fmt_args = vec![];
+		let params_fmt_str = params
+			.map(|s| {
+				if self.no_trace.contains(&s) {
+					format!("{s}: <redacted>")
+				} else {
+					let fmt = format!("{s}: {{:?}}");
+					fmt_args.push(s);
+					fmt
+				}
+			})
+			.collect::<Vec<_>>()
+			.join(", ");
+		let name = self.name.replace('{', "{{").replace('}', "}}");
+		(format!("{name}({params_fmt_str}) = {{:?}}\n"), fmt_args)
+	}
+
+	/// Warns about calls to a `#[deprecated]` host function.
+	///
+	/// The log message is only emitted once while the debug buffer is notified on every call.
+	fn deprecation_warning(&self) -> TokenStream2 {
+		let Some(note) = &self.deprecated else { return quote! {} };
+		let msg = match note.as_str() {
+			"" => format!("syscall `{}` is deprecated", self.name),
+			note => format!("syscall `{}` is deprecated: {note}", self.name),
+		};
+		let debug_msg = format!("{msg}\n");
+		let name = &self.name;
+		quote! {
+			if self.ext().note_deprecated_syscall(#name) {
+				::log::warn!(target: "runtime::revive", "{}", #msg);
+				self.ext().append_debug_buffer(#debug_msg);
+			}
+		}
+	}
+
+	/// An identifier derived from the export symbol which is unique within the environment.
+	fn ident(&self) -> Ident {
+		Ident::new(&symbol_ident(&self.name), self.item.sig.ident.span())
+	}
+
+	/// The name of the variant of the generated `Syscall` enum, i.e. the PascalCased symbol.
+	fn variant(&self) -> Ident {
+		let mut variant = self
+			.ident()
+			.to_string()
+			.split('_')
+			.map(|part| {
+				let mut chars = part.chars();
+				chars.next().map_or_else(String::new, |c| c.to_ascii_uppercase().to_string()) +
+					chars.as_str()
+			})
+			.collect::<String>();
+		if !variant.starts_with(|c: char| c.is_ascii_alphabetic()) {
+			variant.insert(0, '_');
+		}
+		Ident::new(&variant, self.item.sig.ident.span())
+	}
+
+	/// Charges the cost declared with `#[gas]`, if any.
+	fn charge_gas(&self) -> TokenStream2 {
+		match &self.gas {
+			Some(cost) => quote! { self.charge_gas(#cost)?; },
+			None => quote! {},
+		}
+	}
+
+	/// The arguments passed by the guest, i.e. all arguments but `self` and `memory`.
+	fn params(&self) -> impl Iterator<Item = &syn::PatType> + Clone {
+		self.item.sig.inputs.iter().skip(2).filter_map(|arg| match arg {
+			FnArg::Typed(pat) => Some(pat),
+			FnArg::Receiver(_) => None,
+		})
+	}
+
+	/// Whether this host function is only compiled in with the `unstable-hostfn` feature.
+	fn is_unstable_hostfn(&self) -> bool {
+		self.cfg
+			.iter()
+			.any(|cfg| cfg.to_token_stream().to_string().contains("\"unstable-hostfn\""))
+	}
+
+	/// Whether a guest can call this host function with the given API availability.
+	fn is_callable(&self, include_unstable: bool) -> bool {
+		self.tier() != Tier::Removed && (include_unstable || self.api_version.is_some())
+	}
+
+	/// The signature of this host function as seen by the guest.
+	fn signature(&self) -> String {
+		let params = self.param_types().join(", ");
+		match self.returns {
+			HostFnReturn::Unit | HostFnReturn::Array(_) => format!("fn({params})"),
+			_ => format!("fn({params}) -> {}", self.returns.guest_type()),
+		}
+	}
+
+	/// The types of the arguments passed by the guest.
+	fn param_types(&self) -> Vec<String> {
+		self.params().map(|p| p.ty.to_token_stream().to_string()).collect()
+	}
+
+	/// Whether a `bench_*` function is generated for this host function.
+	fn has_bench(&self) -> bool {
+		self.tier() != Tier::Removed && self.alias_of.is_none()
+	}
+
+	/// Whether the host function has a doc comment.
+	fn is_documented(&self) -> bool {
+		self.item.attrs.iter().any(|attr| attr.path().is_ident("doc"))
+	}
+
+	/// Whether the body starts with the guard that rejects state changes in read-only calls.
+	fn has_read_only_guard(&self) -> bool {
+		let Some(error) = &self.mutating else { return false };
+		self.item.block.stmts.first().map(|stmt| stmt.to_token_stream().to_string()) ==
+			Some(read_only_guard(error).to_token_stream().to_string())
+	}
+
+	/// The body without its read-only guard, which the dispatch code runs before charging gas.
+	fn unguarded_block(&self) -> syn::Block {
+		let mut block = (*self.item.block).clone();
+		if self.has_read_only_guard() {
+			block.stmts.remove(0);
+		}
+		block
+	}
+
+	/// Number of argument registers read by this host function.
+	fn registers_used(&self) -> u32 {
+		if self.args_in_memory() {
+			1
+		} else {
+			self.registers_needed()
+		}
+	}
+
+	/// Number of registers needed to pass all arguments in registers.
+	fn registers_needed(&self) -> u32 {
+		self.params().filter_map(|p| arg_registers(&p.ty)).sum()
+	}
+
+	/// Describes which registers each argument is passed in, one line per argument.
+	fn register_layout(&self) -> Vec<String> {
+		if self.args_in_memory() {
+			return vec!["- `a0`: pointer to all arguments packed in guest memory".to_string()]
+		}
+		let mut idx = 0;
+		self.params()
+			.filter_map(|p| {
+				let syn::Pat::Ident(pat_ident) = &*p.pat else { return None };
+				let size = arg_registers(&p.ty)?;
+				let regs = match size {
+					1 => format!("`a{idx}`"),
+					_ => format!("`a{idx}`..`a{}`", idx + size - 1),
+				};
+				idx += size;
+				Some(format!("- {regs}: `{}`", pat_ident.ident))
+			})
+			.collect()
+	}
+
+	/// Describes the order and offset of the arguments packed in guest memory, one line per
+	/// argument.
+	fn packed_layout(&self) -> Vec<String> {
+		let mut offset = 0;
+		self.params()
+			.filter_map(|p| {
+				let syn::Pat::Ident(pat_ident) = &*p.pat else { return None };
+				let size = arg_bytes(&p.ty)?;
+				let ty = p.ty.to_token_stream();
+				let line = format!("- `{}`: `{ty}` at offset {offset}", pat_ident.ident);
+				offset += size;
+				Some(line)
+			})
+			.collect()
+	}
+
+	/// Whether all arguments are passed in guest memory because they exceed `max_regs`.
+	fn args_in_memory(&self) -> bool {
+		self.registers_needed() > self.max_regs
+	}
+
+	fn tier(&self) -> Tier {
+		if self.removed {
+			Tier::Removed
+		} else if self.deprecated.is_some() {
+			Tier::Deprecated
+		} else if self.api_version.is_some() {
+			Tier::Stable
+		} else {
+			Tier::Unstable
+		}
+	}
+}
+
+/// Statement injected at the start of every `#[mutating]` host function.
+///
+/// A single identifier names a variant of the pallet's `Error`. Any other path is used as is.
+fn read_only_guard(error: &syn::Path) -> syn::Stmt {
+	let error: syn::Expr = match error.get_ident() {
+		Some(variant) => parse_quote! { Error::<E::T>::#variant },
+		None => parse_quote! { #error },
+	};
+	parse_quote! {
+		if self.ext().is_read_only() {
+			return Err(#error.into());
+		}
+	}
+}
+
+/// Wraps the body of a `#[read_only]` host function.
+///
+/// The current frame is marked read-only while the body executes so that storage writes fail. The
+/// previous setting is restored afterwards, even if the body returned early.
+fn read_only_block(block: &syn::Block, output: &syn::ReturnType) -> Box<syn::Block> {
+	parse_quote! {{
+		let __scope__ = self.ext().enter_read_only();
+		let __result__ = (|| #output #block)();
+		self.ext().exit_read_only(__scope__);
+		__result__
+	}}
+}
+
+fn is_arg(arg: &FnArg, name: &Ident) -> bool {
+	let FnArg::Typed(pat) = arg else { return false };
+	matches!(*pat.pat, syn::Pat::Ident(ref ident) if ident.ident == *name)
+}
+
 fn is_valid_special_arg(idx: usize, arg: &FnArg) -> bool {
 	match (idx, arg) {
 		(0, FnArg::Receiver(rec)) => rec.reference.is_some() && rec.mutability.is_some(),
@@ -334,83 +1398,497 @@ fn is_valid_special_arg(idx: usize, arg: &FnArg) -> bool {
 	}
 }
 
-fn arg_decoder<'a, P, I>(param_names: P, param_types: I) -> TokenStream2
-where
-	P: Iterator<Item = &'a std::boxed::Box<syn::Pat>> + Clone,
-	I: Iterator<Item = &'a std::boxed::Box<syn::Type>> + Clone,
-{
-	const ALLOWED_REGISTERS: u32 = 6;
+/// Number of registers available to pass arguments to a syscall.
+///
+/// If the arguments don't fit they are passed in memory with a pointer in the first register.
+const ALLOWED_REGISTERS: u32 = 6;
+
+/// Number of bytes a primitive of the given type occupies in guest memory.
+fn byte_size(ident: &Ident) -> Option<usize> {
+	["i8", "u8", "i16", "u16", "i32", "u32", "i64", "u64", "u128"]
+		.iter()
+		.position(|ty| ident == ty)
+		.map(|idx| 1 << (idx / 2))
+}
+
+/// Number of registers needed to pass a primitive of the given type by value.
+///
+/// A `u128` doesn't fit into registers and is passed as a pointer to guest memory instead.
+fn register_size(ident: &Ident) -> Option<u32> {
+	if ident == "i8" ||
+		ident == "i16" ||
+		ident == "i32" ||
+		ident == "u8" ||
+		ident == "u16" ||
+		ident == "u32" ||
+		ident == "u128"
+	{
+		Some(1)
+	} else if ident == "i64" || ident == "u64" {
+		Some(2)
+	} else {
+		None
+	}
+}
+
+/// Whether the type can name a syscall specific error code enum.
+///
+/// Only plain UpperCamelCase paths without generic arguments are accepted, which rules out the
+/// primitives as well as generic containers like `Option<u32>`.
+fn is_error_code_enum(ty: &syn::TypePath) -> bool {
+	let Some(last) = ty.path.segments.last() else { return false };
+	ty.qself.is_none() &&
+		ty.path.segments.iter().all(|segment| segment.arguments.is_none()) &&
+		last.ident.to_string().starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+/// Length of a `[u8; N]` argument, which is passed as a pointer to guest memory.
+fn byte_array_len(ty: &syn::Type) -> Option<usize> {
+	let syn::Type::Array(array) = ty else { return None };
+	let syn::Type::Path(elem) = &*array.elem else { return None };
+	match &array.len {
+		syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. })
+			if elem.path.is_ident("u8") =>
+			lit.base10_parse().ok(),
+		_ => None,
+	}
+}
+
+/// Number of bytes an argument of the given type occupies when packed in guest memory.
+fn arg_bytes(ty: &syn::Type) -> Option<usize> {
+	match ty {
+		syn::Type::Path(path) => path.path.get_ident().and_then(byte_size),
+		ty => byte_array_len(ty),
+	}
+}
+
+/// Number of registers needed to pass an argument of the given type.
+fn arg_registers(ty: &syn::Type) -> Option<u32> {
+	match ty {
+		syn::Type::Path(path) => path.path.get_ident().and_then(register_size),
+		ty => byte_array_len(ty).map(|_| 1),
+	}
+}
+
+/// Replaces every character of an export symbol other than ASCII letters and digits with `_`.
+///
+/// The result is only a valid identifier if the symbol was checked while parsing.
+fn symbol_ident(symbol: &str) -> String {
+	symbol.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+}
+
+/// Traps if a register holds a value which doesn't fit into the narrow integer type `ident`.
+///
+/// Only emitted with the `strict-args` feature. Otherwise out of range values are silently
+/// truncated: the register is controlled by the contract, so an assertion would let any contract
+/// panic the node.
+fn range_check(ident: &Ident, reg: &Ident) -> TokenStream2 {
+	let value = if ident == "u8" || ident == "u16" {
+		quote! { #reg }
+	} else if ident == "i8" || ident == "i16" {
+		quote! { #reg as i32 }
+	} else {
+		return quote! {}
+	};
+	quote! {
+		if <#ident>::try_from(#value).is_err() {
+			return Err(Error::<E::T>::DecodingFailed.into());
+		}
+	}
+}
+
+fn arg_decoder<'a, P, I>(
+	param_names: P,
+	param_types: I,
+	max_regs: u32,
+	strict_args: bool,
+) -> syn::Result<TokenStream2>
+where
+	P: Iterator<Item = &'a std::boxed::Box<syn::Pat>> + Clone,
+	I: Iterator<Item = &'a std::boxed::Box<syn::Type>> + Clone,
+{
 	let mut registers_used = 0;
 	let mut bindings = vec![];
-	for (idx, (name, ty)) in param_names.clone().zip(param_types.clone()).enumerate() {
-		let syn::Type::Path(path) = &**ty else {
-			panic!("Type needs to be path");
-		};
-		let Some(ident) = path.path.get_ident() else {
-			panic!("Type needs to be ident");
+	for (name, ty) in param_names.clone().zip(param_types.clone()) {
+		let err = |msg| syn::Error::new(ty.span(), msg);
+		let ident = match &**ty {
+			syn::Type::Path(path) => match path.path.get_ident() {
+				Some(ident) => Some(ident),
+				None => return Err(err("Type needs to be ident")),
+			},
+			ty if byte_array_len(ty).is_some() => None,
+			_ => return Err(err("Type needs to be path")),
 		};
-		let size = if ident == "i8" ||
-			ident == "i16" ||
-			ident == "i32" ||
-			ident == "u8" ||
-			ident == "u16" ||
-			ident == "u32"
-		{
-			1
-		} else if ident == "i64" || ident == "u64" {
-			2
-		} else {
-			panic!("Pass by value only supports primitives");
+		let Some(size) = arg_registers(ty) else {
+			return Err(err("Pass by value only supports primitives"))
 		};
+		let idx = registers_used;
 		registers_used += size;
-		if registers_used > ALLOWED_REGISTERS {
-			return quote! {
+		if registers_used > max_regs {
+			// The guest passes the arguments packed into a buffer. Its length has to match what
+			// `read_as` reads or the arguments would be decoded from the wrong offsets.
+			let len = param_types
+				.clone()
+				.map(|ty| arg_bytes(ty))
+				.sum::<Option<usize>>()
+				.ok_or_else(|| err("Pass by value only supports primitives"))?;
+			let types = param_types.clone();
+			return Ok(quote! {
+				debug_assert_eq!(
+					<(#( #types, )*) as MaxEncodedLen>::max_encoded_len(),
+					#len,
+					"arguments passed in memory must be packed",
+				);
 				let (#( #param_names, )*): (#( #param_types, )*) = memory.read_as(__a0__)?;
-			}
+			})
 		}
 		let this_reg = quote::format_ident!("__a{}__", idx);
 		let next_reg = quote::format_ident!("__a{}__", idx + 1);
-		let binding = if size == 1 {
-			quote! {
-				let #name = #this_reg as #ty;
-			}
-		} else {
-			quote! {
+		// `as` between integers of the same width keeps the bit pattern and truncation keeps the
+		// low bits. Signed arguments therefore arrive in their two's complement representation.
+		let binding = match ident {
+			// A byte array is passed as a pointer to its contents.
+			None => quote! {
+				let #name: #ty = memory.read_array(#this_reg)?;
+			},
+			Some(ident) if ident == "u128" => quote! {
+				let #name: #ty = memory.read_as(#this_reg)?;
+			},
+			Some(ident) if size == 1 => {
+				let range_check = strict_args.then(|| range_check(ident, &this_reg));
+				quote! {
+					#range_check
+					let #name = #this_reg as #ty;
+				}
+			},
+			Some(_) => quote! {
 				let #name = (#this_reg as #ty) | ((#next_reg as #ty) << 32);
-			}
+			},
 		};
 		bindings.push(binding);
 	}
-	quote! {
+	Ok(quote! {
 		#( #bindings )*
-	}
+	})
 }
 
 /// Expands environment definition.
 /// Should generate source code for:
 ///  - implementations of the host functions to be added to the wasm runtime environment (see
 ///    `expand_impls()`).
-fn expand_env(def: &EnvDef) -> TokenStream2 {
-	let impls = expand_functions(def);
-	let bench_impls = expand_bench_functions(def);
+fn expand_env(def: &EnvDef, config: &EnvConfig) -> TokenStream2 {
+	// Read-only enforcement silently breaks if the guard is ever lost or reordered.
+	if let Some(f) =
+		def.host_funcs.iter().find(|f| f.mutating.is_some() && !f.has_read_only_guard())
+	{
+		let msg = format!("#[mutating] host function `{}` is missing its read-only guard", f.name);
+		return syn::Error::new(f.item.span(), msg).to_compile_error()
+	}
+
+	// Stable syscalls are part of the public interface and need to be documented.
+	let undocumented = def
+		.host_funcs
+		.iter()
+		.find(|f| (f.api_version.is_some() || config.deny_undocumented) && !f.is_documented());
+	if let Some(f) = undocumented {
+		let msg = format!("Host function `{}` is missing a doc comment", f.name);
+		return syn::Error::new(f.item.sig.ident.span(), msg).to_compile_error()
+	}
+
+	let metadata = expand_metadata(def);
+	if config.verifier_only {
+		return metadata
+	}
+
+	let tier = |tier| def.symbols(|f| f.tier() == tier);
+	let (stable, deprecated, unstable, removed) =
+		(tier(Tier::Stable), tier(Tier::Deprecated), tier(Tier::Unstable), tier(Tier::Removed));
+	let writing_memory = def.symbols(|f| f.writes_memory);
+	let context_safe = def.symbols(|f| f.context_safe);
+	// Not gated by `cfg` as the purpose is to document what the feature toggles.
+	let unstable_hostfn = def
+		.host_funcs
+		.iter()
+		.filter(|f| f.is_unstable_hostfn())
+		.map(|f| Literal::byte_string(f.name.as_bytes()));
+	let manifest = def.manifest();
+	let markdown_table = def.markdown_table();
 	let docs = expand_func_doc(def);
+	let interface = quote! {
+		/// The import symbols of all syscalls grouped by their lifecycle stage.
+		pub struct SyscallsByTier {
+			/// Syscalls with an `#[api_version]`.
+			pub stable: &'static [&'static [u8]],
+			/// Syscalls which are still callable but scheduled for removal.
+			pub deprecated: &'static [&'static [u8]],
+			/// Syscalls which are only available for testing.
+			pub unstable: &'static [&'static [u8]],
+			/// Syscalls which were removed and are no longer callable.
+			pub removed: &'static [&'static [u8]],
+		}
+
+		/// Returns the import symbols of all syscalls grouped by their lifecycle stage.
+		pub fn syscalls_by_tier() -> SyscallsByTier {
+			SyscallsByTier {
+				stable: #stable,
+				deprecated: #deprecated,
+				unstable: #unstable,
+				removed: #removed,
+			}
+		}
+
+		/// A JSON array describing every callable syscall.
+		///
+		/// Each entry holds the `name`, whether it is `stable`, its `arity`, the types of its
+		/// `args` and the type it `returns`. Syscalls which are gated by `cfg` are not included.
+		pub const SYSCALL_MANIFEST: &str = #manifest;
+
+		/// A markdown table of every callable syscall for auditors and tools which diff it.
+		///
+		/// It lists the same syscalls as [`SYSCALL_MANIFEST`] with the columns `name`, `args`,
+		/// `return` and `stable`.
+		pub const SYSCALL_TABLE_MD: &str = #markdown_table;
+
+		/// Returns the import symbols of all syscalls that write their results into guest memory.
+		pub fn syscalls_writing_memory() -> &'static [&'static [u8]] {
+			#writing_memory
+		}
+
+		/// Returns the import symbols of all syscalls which are safe to call outside of a full
+		/// transactional context.
+		pub fn context_safe_syscalls() -> &'static [&'static [u8]] {
+			#context_safe
+		}
+
+		/// Returns the import symbols of all syscalls gated behind the `unstable-hostfn` feature.
+		///
+		/// The list is the same regardless of whether the feature is enabled.
+		pub fn unstable_hostfn_syscalls() -> &'static [&'static [u8]] {
+			&[#( #unstable_hostfn ),*]
+		}
+	};
+	let doc_trait = quote! {
+		/// Documentation of the syscalls (host functions) available to contracts.
+		///
+		/// Each of the functions in this trait represent a function that is callable
+		/// by the contract. Guests use the function name as the import symbol.
+		///
+		/// # Note
+		///
+		/// This module is not meant to be used by any code. Rather, it is meant to be
+		/// consumed by humans through rustdoc.
+		#[cfg(doc)]
+		pub trait SyscallDoc {
+			#docs
+		}
+	};
+	if config.docs_only {
+		return quote! {
+			#metadata
+
+			#interface
+
+			#doc_trait
+		}
+	}
+
+	let impls = match expand_functions(def, config) {
+		Ok(impls) => impls,
+		Err(err) => return err.to_compile_error(),
+	};
+	let trace_unknown_symbol = trace_unknown(
+		config,
+		quote! {
+			core::write!(
+				&mut w,
+				"unknown syscall {}\n",
+				alloc::string::String::from_utf8_lossy(__syscall_symbol__),
+			)
+		},
+	);
+	let syscall_enum = match expand_syscall_enum(def) {
+		Ok(syscall_enum) => syscall_enum,
+		Err(err) => return err.to_compile_error(),
+	};
+	let call_validation = expand_call_validation(def);
+	let bench_impls = expand_bench_functions(def);
+	let syscall_wrappers = config.syscall_wrappers.then(|| expand_syscall_wrappers(def));
+	let mock_impls = expand_mock_functions(def);
+	let bench_syscalls = def.bench_funcs().map(|f| {
+		let cfg = &f.cfg;
+		let name = &f.name;
+		quote! { #( #cfg )* #name }
+	});
+	let required_costs = def.host_funcs.iter().filter(|f| f.has_bench()).map(|f| {
+		let cfg = &f.cfg;
+		let name = &f.name;
+		quote! { #( #cfg )* #name }
+	});
 	let highest_api_version =
 		def.host_funcs.iter().filter_map(|f| f.api_version).max().unwrap_or_default();
+	let overlong_syscalls = def.overlong_symbols(config.max_symbol_len);
+	// The first matching arm wins so the highest version has to come first.
+	let mut callable = def.host_funcs.iter().filter(|f| f.is_callable(true)).collect::<Vec<_>>();
+	callable.sort_by_key(|f| core::cmp::Reverse(f.version));
+	let resolve = callable.into_iter().map(|f| {
+		let cfg = &f.cfg;
+		let base_name = f.item.sig.ident.to_string();
+		let symbol = Literal::byte_string(f.name.as_bytes());
+		quote! { #( #cfg )* #base_name => Some(#symbol), }
+	});
+	let sorted_names = def.sorted_names();
+	let abi_hash = def.abi_hash();
+	let costs = def.host_funcs.iter().filter(|f| f.is_callable(true)).map(|f| {
+		let cfg = &f.cfg;
+		let name = &f.name;
+		let cost = f.gas.clone().unwrap_or_else(|| parse_quote! { RuntimeCosts::HostFn });
+		quote! { #( #cfg )* (#name, #cost) }
+	});
 
 	quote! {
+		#metadata
+
 		#[cfg(test)]
 		pub const HIGHEST_API_VERSION: u16 = #highest_api_version;
 
+		/// Syscalls whose import symbol exceeds the configured maximum length.
+		#[cfg(test)]
+		pub const OVERLONG_SYSCALLS: &[&str] = &[#( #overlong_syscalls ),*];
+
+		#interface
+
+		#call_validation
+
+		/// Identifies a syscall by its position in [`SYSCALL_NAMES`].
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+		pub struct SyscallId(pub u16);
+
+		impl SyscallId {
+			/// The name of the identified syscall.
+			pub fn name(self) -> &'static str {
+				SYSCALL_NAMES[usize::from(self.0)]
+			}
+		}
+
+		/// The names of all syscalls in lexicographic order.
+		///
+		/// Also lists the syscalls which are disabled by `cfg` so that the position of each name,
+		/// its [`SyscallId`], doesn't depend on the enabled features.
+		pub const SYSCALL_NAMES: &[&str] = &[#( #sorted_names ),*];
+
+		/// Looks up the [`SyscallId`] of the syscall with the given name without allocating.
+		pub fn intern(name: &str) -> Option<SyscallId> {
+			SYSCALL_NAMES.binary_search(&name).ok().map(|idx| SyscallId(idx as u16))
+		}
+
+		#syscall_enum
+
+		/// Returns all callable syscalls with their declared base cost, the most expensive first.
+		///
+		/// Syscalls without a `#[gas]` attribute are listed with [`RuntimeCosts::HostFn`].
+		pub fn syscalls_by_cost<T: Config>() -> Vec<(&'static str, RuntimeCosts)> {
+			let mut costs = [#( #costs ),*].to_vec();
+			costs.sort_by_key(|(_, cost)| {
+				core::cmp::Reverse(<RuntimeCosts as Token<T>>::weight(cost).ref_time())
+			});
+			costs
+		}
+
+		/// Hash over the names and signatures of all stable syscalls.
+		///
+		/// Syscalls which are gated by `cfg` do not contribute to the hash.
+		pub const SYSCALL_ABI_HASH: [u8; 32] = [#( #abi_hash ),*];
+
+		/// Whether a contract built against the given [`SYSCALL_ABI_HASH`] matches this runtime.
+		pub fn is_compatible_with(contract_abi_hash: [u8; 32]) -> bool {
+			contract_abi_hash == SYSCALL_ABI_HASH
+		}
+
+		/// The syscalls a contract needs which this runtime does not provide as expected.
+		#[derive(Debug, Default, PartialEq, Eq)]
+		pub struct CompatibilityReport<'a> {
+			/// Syscalls which are unknown or not callable anymore.
+			pub missing: Vec<&'a [u8]>,
+			/// Syscalls whose signature differs from the one the contract expects.
+			pub changed: Vec<&'a [u8]>,
+			/// Syscalls which are only callable when the runtime allows unstable interfaces.
+			pub unstable: Vec<&'a [u8]>,
+		}
+
+		impl CompatibilityReport<'_> {
+			/// Whether the contract can be executed by this runtime without unstable interfaces.
+			pub fn is_compatible(&self) -> bool {
+				self.missing.is_empty() && self.changed.is_empty() && self.unstable.is_empty()
+			}
+		}
+
+		/// Checks the syscalls a contract needs against this runtime.
+		///
+		/// `contract_abi_table` lists the import symbol of each syscall together with its
+		/// signature as rendered by [`expected_signature`]. Only syscalls which are compiled in
+		/// are considered available.
+		pub fn compatibility_report<'a>(
+			contract_abi_table: &[(&'a [u8], &str)],
+		) -> CompatibilityReport<'a> {
+			let mut report = CompatibilityReport::default();
+			for &(symbol, signature) in contract_abi_table {
+				match expected_signature(symbol) {
+					None => report.missing.push(symbol),
+					Some(expected) if expected != signature => report.changed.push(symbol),
+					Some(_) if !is_stable_syscall(symbol) => report.unstable.push(symbol),
+					Some(_) => {},
+				}
+			}
+			report
+		}
+
+		/// Returns the import symbol of the callable syscall with the given name.
+		pub fn resolve_symbol(base_name: &str) -> Option<&'static [u8]> {
+			#[allow(unreachable_patterns)]
+			match base_name {
+				#( #resolve )*
+				_ => None,
+			}
+		}
+
+		/// Returns the id of the syscall with the given import symbol.
+		///
+		/// The id can be resolved once when preparing a call and then be passed to
+		/// `Runtime::handle_ecall_by_id` for each call.
+		pub fn resolve_syscall_id(symbol: &[u8]) -> Option<SyscallId> {
+			SYSCALL_NAMES
+				.binary_search_by(|name| name.as_bytes().cmp(symbol))
+				.ok()
+				.map(|idx| SyscallId(idx as u16))
+		}
+
 		impl<'a, E: Ext, M: PolkaVmInstance<E::T>> Runtime<'a, E, M> {
 			fn handle_ecall(
 				&mut self,
 				memory: &mut M,
 				__syscall_symbol__: &[u8],
 				__available_api_version__: ApiVersion,
-			) -> Result<Option<u32>, TrapReason>
+			) -> Result<Option<u64>, TrapReason>
+			{
+				let Some(__syscall_id__) = resolve_syscall_id(__syscall_symbol__) else {
+					#trace_unknown_symbol
+					return Err(TrapReason::SupervisorError(Error::<E::T>::InvalidSyscall.into()))
+				};
+				self.handle_ecall_by_id(memory, __syscall_id__, __available_api_version__)
+			}
+
+			/// Same as `handle_ecall` but dispatches on an id returned by [`resolve_syscall_id`].
+			pub fn handle_ecall_by_id(
+				&mut self,
+				memory: &mut M,
+				__syscall_id__: SyscallId,
+				__available_api_version__: ApiVersion,
+			) -> Result<Option<u64>, TrapReason>
 			{
 				#impls
 			}
+
+			#syscall_wrappers
 		}
 
 		#[cfg(feature = "runtime-benchmarks")]
@@ -418,24 +1896,245 @@ fn expand_env(def: &EnvDef) -> TokenStream2 {
 			#bench_impls
 		}
 
-		/// Documentation of the syscalls (host functions) available to contracts.
+		#mock_impls
+
+		/// The symbols of all syscalls which have a `bench_*` function in declaration order.
+		#[cfg(feature = "runtime-benchmarks")]
+		pub const BENCH_SYSCALLS: &[&str] = &[#( #bench_syscalls ),*];
+
+		/// Returns the index of the syscall with the given symbol in [`BENCH_SYSCALLS`].
+		#[cfg(feature = "runtime-benchmarks")]
+		pub fn symbol_to_index(symbol: &str) -> Option<usize> {
+			BENCH_SYSCALLS.iter().position(|candidate| *candidate == symbol)
+		}
+
+		/// The symbols of all dispatched syscalls which need their own benchmarked weight.
 		///
-		/// Each of the functions in this trait represent a function that is callable
-		/// by the contract. Guests use the function name as the import symbol.
+		/// Aliases share the weight of their target and are not listed.
+		#[cfg(feature = "runtime-benchmarks")]
+		pub const REQUIRED_COSTS: &[&str] = &[#( #required_costs ),*];
+
+		#doc_trait
+	}
+}
+
+/// Generates the functions describing the syscalls without referring to any runtime type.
+///
+/// Those are the only items emitted when the `verifier-only` feature is enabled.
+fn expand_metadata(def: &EnvDef) -> TokenStream2 {
+	let symbols = |include_unstable| def.symbols(|f| f.is_callable(include_unstable));
+	let (all, stable) = (symbols(true), symbols(false));
+	let versioned = |include_unstable| {
+		let symbols = def.host_funcs.iter().filter(|f| f.is_callable(include_unstable)).map(|f| {
+			let cfg = &f.cfg;
+			let symbol = Literal::byte_string(f.name.as_bytes());
+			let version = f.version;
+			quote! { #( #cfg )* (#symbol, #version) }
+		});
+		quote! { &[#( #symbols ),*] }
+	};
+	let (all_versioned, stable_versioned) = (versioned(true), versioned(false));
+	let mut stable_sorted =
+		def.host_funcs.iter().filter(|f| f.is_callable(false)).collect::<Vec<_>>();
+	stable_sorted.sort_by(|a, b| a.name.cmp(&b.name));
+	let stable_sorted = stable_sorted.into_iter().map(|f| {
+		let cfg = &f.cfg;
+		let symbol = Literal::byte_string(f.name.as_bytes());
+		quote! { #( #cfg )* #symbol }
+	});
+	let callable = def.host_funcs.iter().filter(|f| f.is_callable(true));
+	let arities = callable.clone().map(|f| {
+		let cfg = &f.cfg;
+		let symbol = Literal::byte_string(f.name.as_bytes());
+		let arity = f.params().count();
+		quote! { #( #cfg )* #symbol => Some(#arity), }
+	});
+	let signatures = callable.clone().map(|f| {
+		let cfg = &f.cfg;
+		let symbol = Literal::byte_string(f.name.as_bytes());
+		let signature = f.signature();
+		quote! { #( #cfg )* #symbol => Some(#signature), }
+	});
+	let max_outputs = callable.filter_map(|f| {
+		let cfg = &f.cfg;
+		let symbol = Literal::byte_string(f.name.as_bytes());
+		let max_output = f.max_output?;
+		Some(quote! { #( #cfg )* #symbol => Some(#max_output), })
+	});
+
+	quote! {
+		/// The number of syscalls returned by `list_syscalls(true)`.
+		pub const SYSCALL_COUNT: usize = {
+			let syscalls: &[&[u8]] = #all;
+			syscalls.len()
+		};
+
+		/// The number of syscalls returned by `list_syscalls(false)`.
+		pub const STABLE_SYSCALL_COUNT: usize = {
+			let syscalls: &[&[u8]] = #stable;
+			syscalls.len()
+		};
+
+		/// Returns the import symbols of all syscalls a contract can call.
 		///
-		/// # Note
+		/// Unstable syscalls are only included if `include_unstable` is set.
+		pub fn list_syscalls(include_unstable: bool) -> &'static [&'static [u8]] {
+			if include_unstable {
+				#all
+			} else {
+				#stable
+			}
+		}
+
+		/// Same as [`list_syscalls`] but pairs each import symbol with the version of the host
+		/// function, i.e. the `N` of the `sealN` module it belongs to.
+		pub fn list_syscalls_with_versions(
+			include_unstable: bool,
+		) -> &'static [(&'static [u8], u8)] {
+			if include_unstable {
+				#all_versioned
+			} else {
+				#stable_versioned
+			}
+		}
+
+		/// The import symbols of all syscalls returned by `list_syscalls(false)` sorted bytewise.
+		const STABLE_SYSCALLS_SORTED: &[&[u8]] = &[#( #stable_sorted ),*];
+
+		/// Whether the given import symbol belongs to a syscall returned by `list_syscalls(false)`.
+		pub fn is_stable_syscall(symbol: &[u8]) -> bool {
+			STABLE_SYSCALLS_SORTED.binary_search(&symbol).is_ok()
+		}
+
+		/// Returns the number of arguments the syscall with the given import symbol expects.
+		pub fn syscall_arity(symbol: &[u8]) -> Option<usize> {
+			match symbol {
+				#( #arities )*
+				_ => None,
+			}
+		}
+
+		/// Returns the signature of the syscall with the given import symbol, e.g. `fn(u32) -> u32`.
+		pub fn expected_signature(symbol: &[u8]) -> Option<&'static str> {
+			match symbol {
+				#( #signatures )*
+				_ => None,
+			}
+		}
+
+		/// Returns the maximum number of bytes the syscall with the given import symbol writes
+		/// into guest memory.
 		///
-		/// This module is not meant to be used by any code. Rather, it is meant to be
-		/// consumed by humans through rustdoc.
-		#[cfg(doc)]
-		pub trait SyscallDoc {
-			#docs
+		/// `None` if the syscall is unknown or its output is unbounded.
+		pub fn syscall_max_output(symbol: &[u8]) -> Option<u32> {
+			match symbol {
+				#( #max_outputs )*
+				_ => None,
+			}
+		}
+	}
+}
+
+/// Generates `validate_call` which rejects calls that set registers not read by the syscall.
+fn expand_call_validation(def: &EnvDef) -> TokenStream2 {
+	let registers = def.host_funcs.iter().filter(|f| f.is_callable(true)).map(|f| {
+		let cfg = &f.cfg;
+		let symbol = Literal::byte_string(f.name.as_bytes());
+		let registers = f.registers_used() as usize;
+		quote! { #( #cfg )* #symbol => #registers, }
+	});
+
+	quote! {
+		/// Error returned by [`validate_call`].
+		#[derive(Debug, PartialEq, Eq)]
+		pub enum CallError {
+			/// There is no callable syscall with the given symbol.
+			UnknownSyscall,
+			/// The register with this index is set but not read by the syscall.
+			DirtyRegister(usize),
+		}
+
+		/// Checks that a call to `symbol` only sets the argument registers the syscall reads.
+		pub fn validate_call(symbol: &[u8], regs: &[u64; 6]) -> Result<(), CallError> {
+			let used = match symbol {
+				#( #registers )*
+				_ => return Err(CallError::UnknownSyscall),
+			};
+			match regs.iter().enumerate().skip(used).find(|(_, reg)| **reg != 0) {
+				Some((idx, _)) => Err(CallError::DirtyRegister(idx)),
+				None => Ok(()),
+			}
+		}
+	}
+}
+
+/// Generates the `Syscall` enum with one variant per host function.
+fn expand_syscall_enum(def: &EnvDef) -> syn::Result<TokenStream2> {
+	let mut seen = BTreeMap::new();
+	for f in &def.host_funcs {
+		let variant = f.variant().to_string();
+		if let Some(other) = seen.insert(variant.clone(), &f.name) {
+			if *other != f.name {
+				let msg = format!(
+					"Syscalls `{other}` and `{}` map to the same variant `{variant}`",
+					f.name
+				);
+				return Err(syn::Error::new(f.item.sig.ident.span(), msg))
+			}
 		}
 	}
+
+	let variants = def.host_funcs.iter().map(|f| {
+		let cfg = &f.cfg;
+		let variant = f.variant();
+		let doc = format!("The `{}` syscall.", f.name);
+		quote! {
+			#[doc = #doc]
+			#( #cfg )*
+			#variant
+		}
+	});
+	let symbols = def.host_funcs.iter().map(|f| {
+		let cfg = &f.cfg;
+		let variant = f.variant();
+		let symbol = Literal::byte_string(f.name.as_bytes());
+		(
+			quote! { #( #cfg )* Self::#variant => #symbol, },
+			quote! { #( #cfg )* #symbol => Some(Self::#variant), },
+		)
+	});
+	let (as_symbol, from_symbol): (Vec<_>, Vec<_>) = symbols.unzip();
+
+	Ok(quote! {
+		/// All syscalls defined by the environment.
+		#[non_exhaustive]
+		#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+		pub enum Syscall {
+			#( #variants, )*
+		}
+
+		impl Syscall {
+			/// The import symbol of the syscall.
+			pub fn as_symbol(&self) -> &'static [u8] {
+				match *self {
+					#( #as_symbol )*
+				}
+			}
+
+			/// Returns the syscall with the given import symbol.
+			pub fn from_symbol(symbol: &[u8]) -> Option<Self> {
+				match symbol {
+					#( #from_symbol )*
+					_ => None,
+				}
+			}
+		}
+	})
 }
 
-fn expand_functions(def: &EnvDef) -> TokenStream2 {
-	let impls = def.host_funcs.iter().map(|f| {
+/// Generates the body of `handle_ecall_by_id`.
+fn expand_functions(def: &EnvDef, config: &EnvConfig) -> syn::Result<TokenStream2> {
+	let impls = def.host_funcs.iter().filter(|f| f.tier() != Tier::Removed).map(|f| {
 		// skip the self and memory argument
 		let params = f.item.sig.inputs.iter().skip(2);
 		let param_names = params.clone().filter_map(|arg| {
@@ -450,39 +2149,70 @@ fn expand_functions(def: &EnvDef) -> TokenStream2 {
 			};
 			Some(&arg.ty)
 		});
-		let arg_decoder = arg_decoder(param_names, param_types);
+		let arg_decoder = arg_decoder(param_names, param_types, f.max_regs, config.strict_args)?;
 		let cfg = &f.cfg;
 		let name = &f.name;
-		let syscall_symbol = Literal::byte_string(name.as_bytes());
-		let body = &f.item.block;
+		let id = def.syscall_id(name);
+		let body = if config.syscall_wrappers {
+			let method = quote::format_ident!("syscall_{}", f.ident());
+			let args = f.params().filter_map(|p| match &*p.pat {
+				syn::Pat::Ident(pat_ident) => Some(&pat_ident.ident),
+				_ => None,
+			});
+			parse_quote! {{ self.#method(memory, #( #args ),*) }}
+		} else {
+			f.unguarded_block()
+		};
+		// A read-only caller is rejected before being charged.
+		let read_only_guard = f.has_read_only_guard().then(|| &f.item.block.stmts[0]);
+		let charge_gas = f.charge_gas();
+		let deprecation_warning = f.deprecation_warning();
+		// The body can't write memory if it only asked for a shared reference.
+		let shared_memory = (!f.memory_mut).then(|| {
+			quote! {
+				#[allow(unused_variables)]
+				let memory: &M = &*memory;
+			}
+		});
 		let map_output = f.returns.map_output();
-		let output = &f.item.sig.output;
+		let output = match f.returns {
+			HostFnReturn::Array(_) => parse_quote! { -> Result<(), TrapReason> },
+			_ => f.item.sig.output.clone(),
+		};
 		let api_version = match f.api_version {
 			Some(version) => quote! { Some(#version) },
 			None => quote! { None },
 		};
 
+		// Only the body is timed, not charging gas, decoding arguments or tracing.
+		let call_body = if config.host_timing {
+			quote! {{
+				let __started__ = std::time::Instant::now();
+				let __result__ = (|| #body)();
+				self.ext().record_host_time(#name, __started__.elapsed());
+				__result__
+			}}
+		} else {
+			quote! { (|| #body)() }
+		};
+
 		// wrapped host function body call with host function traces
 		// see https://github.com/paritytech/polkadot-sdk/tree/master/substrate/frame/contracts#host-function-tracing
-		let wrapped_body_with_trace = {
-			let trace_fmt_args = params.clone().filter_map(|arg| match arg {
-				syn::FnArg::Receiver(_) => None,
-				syn::FnArg::Typed(p) => match *p.pat.clone() {
-					syn::Pat::Ident(ref pat_ident) => Some(pat_ident.ident.clone()),
-					_ => None,
-				},
-			});
-
-			let params_fmt_str = trace_fmt_args
-				.clone()
-				.map(|s| format!("{s}: {{:?}}"))
-				.collect::<Vec<_>>()
-				.join(", ");
-			let trace_fmt_str = format!("{}({}) = {{:?}}\n", name, params_fmt_str);
+		let wrapped_body_with_trace = if !config.host_trace {
+			call_body
+		} else if config.structured_trace {
+			let trace_args = f.trace_args();
+			quote! {
+				let result = #call_body;
+				self.ext().trace_syscall(#name, &[#( #trace_args ),*], &result);
+				result
+			}
+		} else {
+			let (trace_fmt_str, trace_fmt_args) = f.trace_fmt();
 
 			quote! {
 				// wrap body in closure to make sure the tracing is always executed
-				let result = (|| #body)();
+				let result = #call_body;
 				if ::log::log_enabled!(target: "runtime::revive::strace", ::log::Level::Trace) {
 						use core::fmt::Write;
 						let mut w = sp_std::Writer::default();
@@ -493,20 +2223,39 @@ fn expand_functions(def: &EnvDef) -> TokenStream2 {
 				result
 			}
 		};
+		let wrapped_body_with_trace = match (&f.returns, &f.item.sig.output) {
+			(HostFnReturn::Array(_), syn::ReturnType::Type(_, ty)) => quote! {
+				let __output__: #ty = { #wrapped_body_with_trace };
+				memory.write(out_ptr, &__output__?)?;
+				Ok(())
+			},
+			_ => wrapped_body_with_trace,
+		};
 
-		quote! {
-			#cfg
-			#syscall_symbol if __is_available__(#api_version) => {
+		Ok(quote! {
+			#( #cfg )*
+			#id if __is_available__(#api_version) => {
 				// closure is needed so that "?" can infere the correct type
 				(|| #output {
+					#read_only_guard
+					#charge_gas
+					#deprecation_warning
 					#arg_decoder
+					#shared_memory
 					#wrapped_body_with_trace
 				})().map(#map_output)
 			},
-		}
+		})
 	});
+	let impls = impls.collect::<syn::Result<Vec<_>>>()?;
+	let base_cost = &config.base_cost;
+	// Ids of syscalls which are disabled by `cfg` end up here, too.
+	let trace_unknown = trace_unknown(
+		config,
+		quote! { core::write!(&mut w, "unknown syscall id {}\n", __syscall_id__.0) },
+	);
 
-	quote! {
+	Ok(quote! {
 		// Write gas from  polkavm into pallet-revive before entering the host function.
 		let __gas_left_before__ = self
 			.ext
@@ -515,7 +2264,7 @@ fn expand_functions(def: &EnvDef) -> TokenStream2 {
 			.map_err(TrapReason::from)?;
 
 		// This is the overhead to call an empty syscall that always needs to be charged.
-		self.charge_gas(crate::wasm::RuntimeCosts::HostFn).map_err(TrapReason::from)?;
+		self.charge_gas(#base_cost).map_err(TrapReason::from)?;
 
 		// Not all APIs are available depending on configuration or when the code was deployed.
 		// This closure will be used by syscall specific code to perform this check.
@@ -533,31 +2282,79 @@ fn expand_functions(def: &EnvDef) -> TokenStream2 {
 		let (__a0__, __a1__, __a2__, __a3__, __a4__, __a5__) = memory.read_input_regs();
 
 		// Execute the syscall specific logic in a closure so that the gas metering code is always executed.
-		let result = (|| match __syscall_symbol__ {
+		let result = (|| match __syscall_id__.0 {
 			#( #impls )*
-			_ => Err(TrapReason::SupervisorError(Error::<E::T>::InvalidSyscall.into()))
+			_ => {
+				#trace_unknown
+				Err(TrapReason::SupervisorError(Error::<E::T>::InvalidSyscall.into()))
+			}
 		})();
 
 		// Write gas from pallet-revive into polkavm after leaving the host function.
 		let gas = self.ext.gas_meter_mut().sync_to_executor(__gas_left_before__).map_err(TrapReason::from)?;
 		memory.set_gas(gas.into());
 		result
+	})
+}
+
+/// Appends the message written by `write` to the debug buffer when a syscall is not found.
+///
+/// Only formatted if the trace target is enabled so that no allocation happens otherwise.
+fn trace_unknown(config: &EnvConfig, write: TokenStream2) -> Option<TokenStream2> {
+	(config.host_trace && !config.structured_trace).then(|| {
+		quote! {
+			if ::log::log_enabled!(target: "runtime::revive::strace", ::log::Level::Trace) {
+				use core::fmt::Write;
+				let mut w = sp_std::Writer::default();
+				let _ = #write;
+				let msg = core::str::from_utf8(&w.inner()).unwrap_or_default();
+				self.ext().append_debug_buffer(msg);
+			}
+		}
+	})
+}
+
+/// Generates a `syscall_<symbol>` method per host function holding its body.
+///
+/// Only used with the `syscall-wrappers` feature. The dispatch code then calls those methods
+/// instead of inlining every body into `handle_ecall`.
+fn expand_syscall_wrappers(def: &EnvDef) -> TokenStream2 {
+	let impls = def.host_funcs.iter().filter(|f| f.tier() != Tier::Removed).map(|f| {
+		// keep the memory argument as declared in case it is unused
+		let params = f.item.sig.inputs.iter().skip(1);
+		let cfg = &f.cfg;
+		let body = f.unguarded_block();
+		let output = &f.item.sig.output;
+
+		let name = quote::format_ident!("syscall_{}", f.ident());
+		quote! {
+			#( #cfg )*
+			#[inline(always)]
+			fn #name(&mut self, #(#params),*) #output
+				#body
+		}
+	});
+
+	quote! {
+		#( #impls )*
 	}
 }
 
 fn expand_bench_functions(def: &EnvDef) -> TokenStream2 {
-	let impls = def.host_funcs.iter().map(|f| {
+	let impls = def.bench_funcs().map(|f| {
 		// skip the context and memory argument
 		let params = f.item.sig.inputs.iter().skip(2);
 		let cfg = &f.cfg;
-		let name = &f.name;
-		let body = &f.item.block;
+		// Benchmarks measure the raw cost of the body without the read-only check.
+		let body = f.unguarded_block();
+		let charge_gas = f.charge_gas();
 		let output = &f.item.sig.output;
 
-		let name = Ident::new(&format!("bench_{name}"), Span::call_site());
+		let name = quote::format_ident!("bench_{}", f.ident());
 		quote! {
-			#cfg
+			#( #cfg )*
 			pub fn #name(&mut self, memory: &mut M, #(#params),*) #output {
+				#charge_gas
 				#body
 			}
 		}
@@ -568,16 +2365,54 @@ fn expand_bench_functions(def: &EnvDef) -> TokenStream2 {
 	}
 }
 
+/// Generates a `mock_<symbol>` function per callable host function for unit tests.
+///
+/// Each one dispatches the host function on a fresh `Runtime` which reads its arguments from the
+/// registers of the passed memory.
+fn expand_mock_functions(def: &EnvDef) -> TokenStream2 {
+	let impls = def.host_funcs.iter().filter(|f| f.is_callable(true)).map(|f| {
+		let cfg = &f.cfg;
+		let name = quote::format_ident!("mock_{}", f.ident());
+		let symbol = Literal::byte_string(f.name.as_bytes());
+		let doc =
+			format!("Calls the `{}` syscall with the registers and memory of `memory`.", f.name);
+		quote! {
+			#[doc = #doc]
+			#[cfg(test)]
+			// Only the host functions under test are called.
+			#[allow(dead_code)]
+			#( #cfg )*
+			pub fn #name<E: Ext, M: PolkaVmInstance<E::T>>(
+				ext: &mut E,
+				memory: &mut M,
+			) -> Result<Option<u64>, TrapReason> {
+				Runtime::<E, M>::new(ext, Vec::new())
+					.handle_ecall(memory, #symbol, ApiVersion::UnsafeNewest)
+			}
+		}
+	});
+
+	quote! {
+		#( #impls )*
+	}
+}
+
 fn expand_func_doc(def: &EnvDef) -> TokenStream2 {
 	let docs = def.host_funcs.iter().map(|func| {
 		// Remove auxiliary args: `ctx: _` and `memory: _`
 		let func_decl = {
 			let mut sig = func.item.sig.clone();
+			sig.ident = func.ident();
+			// A `#[slice]` is shown as a single `&[u8]` instead of its pointer and length.
 			sig.inputs = sig
 				.inputs
 				.iter()
 				.skip(2)
-				.map(|p| p.clone())
+				.filter(|p| !func.slices.iter().any(|slice| is_arg(p, &slice.len)))
+				.map(|p| match func.slices.iter().find(|slice| is_arg(p, &slice.ptr)) {
+					Some(SliceArg { name, .. }) => parse_quote! { #name: &[u8] },
+					None => p.clone(),
+				})
 				.collect::<Punctuated<FnArg, Comma>>();
 			sig.output = func.returns.success_type();
 			sig.to_token_stream()
@@ -600,9 +2435,127 @@ fn expand_func_doc(def: &EnvDef) -> TokenStream2 {
 				"\n# Unstable API\nThis API is not standardized and only available for testing.";
 				quote! { #[doc = #info] }
 			};
+			let output = match func.returns {
+				HostFnReturn::Array(len) => {
+					let info = format!(
+						"\n# Output\nWrites the resulting `[u8; {len}]` to the guest memory at `out_ptr`."
+					);
+					quote! { #[doc = #info] }
+				},
+				HostFnReturn::U64 | HostFnReturn::I64 => {
+					let info =
+						"\n# Output\nThe low half of the result is returned in `a0` and the \
+						high half in `a1`.";
+					quote! { #[doc = #info] }
+				},
+				HostFnReturn::U32Pair => {
+					let info = "\n# Output\nBoth values are packed into a `u64` as \
+						`(first as u64) << 32 | second as u64`. The first value is returned in `a1` \
+						and the second one in `a0`.";
+					quote! { #[doc = #info] }
+				},
+				_ => quote! {},
+			};
+			let lifecycle = match func.tier() {
+				Tier::Deprecated => {
+					let mut info =
+						"\n# Deprecated\nThis API is deprecated and will be removed.".to_string();
+					if let Some(note) = func.deprecated.as_ref().filter(|note| !note.is_empty()) {
+						info = format!("{info}\n\n{note}");
+					}
+					quote! { #[doc = #info] }
+				},
+				Tier::Removed => {
+					let info = "\n# Removed\nThis API was removed and can no longer be called.";
+					quote! { #[doc = #info] }
+				},
+				Tier::Stable | Tier::Unstable => quote! {},
+			};
+			let names = |by_ref: fn(&syn::Type) -> bool| {
+				func.params()
+					.filter(|p| by_ref(&p.ty))
+					.filter_map(|p| match &*p.pat {
+						syn::Pat::Ident(pat_ident) => Some(format!("`{}`", pat_ident.ident)),
+						_ => None,
+					})
+					.collect::<Vec<_>>()
+			};
+			let mut by_ref = vec![];
+			let u128s = names(|ty| ty.to_token_stream().to_string() == "u128");
+			if !u128s.is_empty() {
+				by_ref.push(format!(
+					"{} of type `u128` {} passed as a pointer to {} little endian encoding in \
+					 guest memory.",
+					u128s.join(", "),
+					if u128s.len() == 1 { "is" } else { "are" },
+					if u128s.len() == 1 { "its" } else { "their" },
+				));
+			}
+			let arrays = names(|ty| byte_array_len(ty).is_some());
+			if !arrays.is_empty() {
+				by_ref.push(format!(
+					"{} {} passed as a pointer to the bytes in guest memory.",
+					arrays.join(", "),
+					if arrays.len() == 1 { "is" } else { "are" },
+				));
+			}
+			by_ref.extend(func.slices.iter().map(|SliceArg { name, ptr, len }| {
+				format!(
+					"`{name}` is passed as a pointer `{ptr}` to its bytes in guest memory and \
+					 their length `{len}`."
+				)
+			}));
+			let by_ref = (!by_ref.is_empty() && !func.args_in_memory()).then(|| {
+				let info = format!("\n# Arguments passed by reference\n{}", by_ref.join("\n"));
+				quote! { #[doc = #info] }
+			});
+			let registers = func.register_layout();
+			let registers = (!registers.is_empty()).then(|| {
+				let info = format!("\n# Registers\n{}", registers.join("\n"));
+				quote! { #[doc = #info] }
+			});
+			let packed = func.args_in_memory().then(|| {
+				let info = format!(
+					"\n# Arguments passed in memory\nThe arguments don't fit into the registers. \
+					 They are packed into a buffer in guest memory in the following order:\n{}",
+					func.packed_layout().join("\n"),
+				);
+				quote! { #[doc = #info] }
+			});
+			let alias = func.alias_of.as_ref().map(|canonical| {
+				let info = format!("\n# Alias\nThis is an alias of `{canonical}`.");
+				quote! { #[doc = #info] }
+			});
+			let read_only = func.read_only.then(|| {
+				let info =
+					"\n# Read-only\nThe contract is treated as read-only while this host function \
+					executes, so any attempt to write storage fails with \
+					`Error::StateChangeDenied`.";
+				quote! { #[doc = #info] }
+			});
+			let category = func.category.as_ref().map(|category| {
+				let info = format!("\n**Category:** {category}");
+				quote! { #[doc = #info] }
+			});
+			let mutates = {
+				let info = format!(
+					"\n**Mutates state:** {}",
+					if func.mutating.is_some() { "yes" } else { "no" }
+				);
+				quote! { #[doc = #info] }
+			};
 			quote! {
 				#func_docs
+				#registers
+				#by_ref
+				#packed
+				#read_only
+				#alias
+				#category
+				#mutates
+				#output
 				#availability
+				#lifecycle
 			}
 		};
 		quote! {
@@ -615,3 +2568,1762 @@ fn expand_func_doc(def: &EnvDef) -> TokenStream2 {
 		#( #docs )*
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proc_macro2::Span;
+
+	fn env_def(item: syn::ItemMod) -> EnvDef {
+		EnvDef::try_from(item).expect("valid environment definition")
+	}
+
+	#[test]
+	fn overlong_symbols_are_reported() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn short(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn this_is_a_deliberately_overlong_syscall_name(
+					&mut self,
+					memory: &mut M,
+				) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		assert_eq!(
+			def.overlong_symbols(DEFAULT_MAX_SYMBOL_LEN),
+			vec!["this_is_a_deliberately_overlong_syscall_name"],
+		);
+		assert!(def.overlong_symbols(64).is_empty());
+	}
+
+	#[test]
+	fn max_symbol_len_is_configurable() {
+		let config: EnvConfig = parse_quote!(max_symbol_len = 5);
+		assert_eq!(config.max_symbol_len, 5);
+
+		let config: EnvConfig = parse_quote!();
+		assert_eq!(config.max_symbol_len, DEFAULT_MAX_SYMBOL_LEN);
+
+		assert!(syn::parse_str::<EnvConfig>("unknown = 5").is_err());
+	}
+
+	#[test]
+	fn base_cost_is_configurable() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn noop(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let expanded = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(expanded.contains("self . charge_gas (crate :: wasm :: RuntimeCosts :: HostFn)"));
+
+		let config: EnvConfig = parse_quote!(base_cost = RuntimeCosts::HostFnScaled);
+		let expanded = expand_functions(&def, &config).unwrap().to_string();
+		assert!(expanded.contains("self . charge_gas (RuntimeCosts :: HostFnScaled)"));
+	}
+
+	#[test]
+	fn mutating_functions_start_with_read_only_guard() {
+		let mut def = env_def(parse_quote! {
+			pub mod env {
+				#[mutating]
+				fn set(&mut self, memory: &mut M, value: u32) -> Result<(), TrapReason> {
+					self.set(value)
+				}
+			}
+		});
+
+		let first = def.host_funcs[0].item.block.stmts[0].to_token_stream().to_string();
+		assert!(first.starts_with("if self . ext () . is_read_only ()"));
+		assert!(def.host_funcs[0].has_read_only_guard());
+		assert!(expand_functions(&def, &Default::default())
+			.unwrap()
+			.to_string()
+			.contains("is_read_only"));
+
+		def.host_funcs[0].item.block.stmts.remove(0);
+		let expanded = expand_env(&def, &Default::default()).to_string();
+		assert!(expanded.contains("compile_error"));
+	}
+
+	#[test]
+	fn read_only_guard_runs_before_gas_is_charged() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[mutating]
+				#[gas(RuntimeCosts::SetCodeHash)]
+				fn set_code_hash(&mut self, memory: &mut M, code_hash_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		for syscall_wrappers in [false, true] {
+			let config = EnvConfig { syscall_wrappers, ..Default::default() };
+			let mut expanded = expand_functions(&def, &config).unwrap().to_string();
+			if syscall_wrappers {
+				expanded += &expand_syscall_wrappers(&def).to_string();
+			}
+			let guard = expanded.find("if self . ext () . is_read_only ()").unwrap();
+			let charge = expanded.find("self . charge_gas (RuntimeCosts :: SetCodeHash)").unwrap();
+			assert!(guard < charge);
+			assert_eq!(expanded.matches("is_read_only").count(), 1);
+		}
+	}
+
+	#[test]
+	fn mutating_is_documented() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[mutating]
+				fn set(&mut self, memory: &mut M, value: u32) -> Result<(), TrapReason> {
+					self.set(value)
+				}
+
+				fn get(&mut self, memory: &mut M) -> Result<u32, TrapReason> {
+					Ok(0)
+				}
+			}
+		});
+
+		let docs = expand_func_doc(&def).to_string();
+		let (set, get) = docs.split_once("fn set").unwrap();
+		assert!(set.contains("**Mutates state:** yes"));
+		assert!(get.contains("**Mutates state:** no"));
+	}
+
+	#[test]
+	fn bench_functions_skip_read_only_guard() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[mutating]
+				fn set(&mut self, memory: &mut M, value: u32) -> Result<(), TrapReason> {
+					self.set(value)
+				}
+			}
+		});
+
+		let bench = expand_bench_functions(&def).to_string();
+		assert!(bench.contains("pub fn bench_set"));
+		assert!(bench.contains("self . set (value)"));
+		assert!(!bench.contains("is_read_only"));
+		assert!(def.host_funcs[0].has_read_only_guard());
+	}
+
+	#[test]
+	fn read_only_functions_run_in_read_only_context() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[read_only]
+				fn peek(&mut self, memory: &mut M) -> Result<u32, TrapReason> {
+					Ok(0)
+				}
+			}
+		});
+
+		let f = &def.host_funcs[0];
+		assert!(f.read_only);
+		assert_eq!(
+			f.item.block.to_token_stream().to_string(),
+			"{ let __scope__ = self . ext () . enter_read_only () ; \
+			 let __result__ = (| | -> Result < u32 , TrapReason > { Ok (0) }) () ; \
+			 self . ext () . exit_read_only (__scope__) ; __result__ }"
+		);
+		assert!(expand_func_doc(&def).to_string().contains("# Read-only"));
+
+		let err = HostFn::try_from(parse_quote! {
+			#[read_only]
+			#[mutating]
+			fn peek(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+				Ok(())
+			}
+		})
+		.err()
+		.unwrap();
+		assert!(err.to_string().ends_with("#[read_only] and #[mutating] are mutually exclusive"));
+	}
+
+	#[test]
+	fn memory_can_be_shared() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn peek(&mut self, memory: &M, ptr: u32) -> Result<u32, TrapReason> {
+					Ok(0)
+				}
+
+				fn poke(&mut self, memory: &mut M, ptr: u32) -> Result<u32, TrapReason> {
+					Ok(0)
+				}
+			}
+		});
+
+		assert!(!def.host_funcs[0].memory_mut);
+		assert!(def.host_funcs[1].memory_mut);
+		let config = EnvConfig { host_trace: false, ..Default::default() };
+		let expanded = expand_functions(&def, &config).unwrap().to_string();
+		let shared = "# [allow (unused_variables)] let memory : & M = & * memory ;";
+		assert_eq!(expanded.matches(shared).count(), 1);
+
+		for item in [
+			parse_quote! {
+				#[writes_memory]
+				fn poke(&mut self, memory: &M, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			},
+			parse_quote! {
+				fn hash(&mut self, memory: &M, out_ptr: u32) -> Result<[u8; 32], TrapReason> {
+					Ok([0; 32])
+				}
+			},
+		] {
+			let err = HostFn::try_from(item).err().unwrap();
+			assert!(err
+				.to_string()
+				.ends_with("Host functions writing memory must take `memory: &mut M`"));
+		}
+	}
+
+	#[test]
+	fn mutating_error_is_configurable() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[mutating(error = ReadOnlyViolation)]
+				fn set(&mut self, memory: &mut M, value: u32) -> Result<(), TrapReason> {
+					self.set(value)
+				}
+
+				#[mutating(error = MyError::ReadOnly)]
+				fn clear(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					self.clear()
+				}
+			}
+		});
+
+		let guard =
+			|idx: usize| def.host_funcs[idx].item.block.stmts[0].to_token_stream().to_string();
+		assert!(
+			guard(0).contains("return Err (Error :: < E :: T > :: ReadOnlyViolation . into ()) ;")
+		);
+		assert!(guard(1).contains("return Err (MyError :: ReadOnly . into ()) ;"));
+		assert!(def.host_funcs.iter().all(HostFn::has_read_only_guard));
+
+		let item: syn::ItemFn = parse_quote! {
+			#[mutating(error = "StateChangeDenied")]
+			fn set(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+				Ok(())
+			}
+		};
+		assert!(HostFn::try_from(item).err().unwrap().to_string().ends_with("Expected a path"));
+	}
+
+	#[test]
+	fn context_safe_syscalls_are_listed() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[context_safe]
+				fn hash(&mut self, memory: &mut M, ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn call(&mut self, memory: &mut M, ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let expanded = expand_env(&def, &Default::default()).to_string();
+		assert!(expanded.contains(
+			"fn context_safe_syscalls () -> & 'static [& 'static [u8]] { & [b\"hash\"] }"
+		));
+	}
+
+	#[test]
+	fn unstable_hostfn_syscalls_are_detected() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[cfg(feature = "unstable-hostfn")]
+				fn experimental(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[cfg(feature = "runtime-benchmarks")]
+				fn noop(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let detected = def.host_funcs.iter().map(HostFn::is_unstable_hostfn).collect::<Vec<_>>();
+		assert_eq!(detected, vec![true, false]);
+		let expanded = expand_env(&def, &Default::default()).to_string();
+		assert!(expanded.contains(
+			"fn unstable_hostfn_syscalls () -> & 'static [& 'static [u8]] { & [b\"experimental\"] }"
+		));
+	}
+
+	#[test]
+	fn syscall_names_are_sorted() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn value_transferred(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[cfg(feature = "runtime-benchmarks")]
+				fn noop(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let expanded = expand_env(&def, &Default::default()).to_string();
+		assert!(expanded.contains(
+			"pub const SYSCALL_NAMES : & [& str] = & [\"caller\" , \"noop\" , \"value_transferred\"] ;"
+		));
+	}
+
+	#[test]
+	fn narrow_args_are_range_checked() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn narrow(&mut self, memory: &mut M, a: u8, b: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let strict = EnvConfig { strict_args: true, ..Default::default() };
+		let expanded = expand_functions(&def, &strict).unwrap().to_string();
+		assert!(expanded.contains(
+			"if < u8 > :: try_from (__a0__) . is_err () { return Err (Error :: < E :: T > :: \
+			 DecodingFailed . into ()) ; }"
+		));
+		assert!(!expanded.contains("try_from (__a1__)"));
+
+		let relaxed = EnvConfig { strict_args: false, ..Default::default() };
+		let expanded = expand_functions(&def, &relaxed).unwrap().to_string();
+		assert!(!expanded.contains("try_from (__a0__)"));
+		assert!(!expanded.contains("assert"));
+	}
+
+	#[test]
+	fn signed_args_are_decoded_from_their_registers() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn signed(
+					&mut self,
+					memory: &mut M,
+					a: i64,
+					b: i32,
+					c: i8,
+				) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let expanded = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(expanded.contains("let a = (__a0__ as i64) | ((__a1__ as i64) << 32) ;"));
+		assert!(expanded.contains("let b = __a2__ as i32 ;"));
+		assert!(expanded.contains("let c = __a3__ as i8 ;"));
+	}
+
+	#[test]
+	fn signed_results_are_supported() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn minus_one(&mut self, memory: &mut M) -> Result<i32, TrapReason> {
+					Ok(-1i32)
+				}
+			}
+		});
+
+		let f = &def.host_funcs[0];
+		assert_eq!(f.signature(), "fn() -> i32");
+		assert_eq!(f.returns.success_type().to_token_stream().to_string(), "-> i32");
+		let expanded = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(expanded.contains(". map (| ret_val | Some (u64 :: from (ret_val as u32)))"));
+	}
+
+	#[test]
+	fn wide_results_are_supported() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn block_number(&mut self, memory: &mut M) -> Result<u64, TrapReason> {
+					Ok(u64::MAX)
+				}
+				fn offset(&mut self, memory: &mut M) -> Result<i64, TrapReason> {
+					Ok(-1i64)
+				}
+			}
+		});
+
+		let (block_number, offset) = (&def.host_funcs[0], &def.host_funcs[1]);
+		assert_eq!(block_number.signature(), "fn() -> u64");
+		assert_eq!(offset.signature(), "fn() -> i64");
+		assert_eq!(offset.returns.success_type().to_token_stream().to_string(), "-> i64");
+		let expanded = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(expanded.contains(". map (| ret_val | Some (ret_val))"));
+		assert!(expanded.contains(". map (| ret_val | Some (ret_val as u64))"));
+	}
+
+	#[test]
+	fn u32_pairs_are_packed() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn range(&mut self, memory: &mut M) -> Result<(u32, u32), TrapReason> {
+					Ok((1, 2))
+				}
+			}
+		});
+
+		let f = &def.host_funcs[0];
+		assert_eq!(f.signature(), "fn() -> u64");
+		let expanded = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(expanded.contains(
+			"| (first , second) : (u32 , u32) | Some (u64 :: from (first) << 32 | u64 :: from \
+			 (second))"
+		));
+		let docs = expand_func_doc(&def).to_string();
+		assert!(docs.contains("fn range () -> (u32 , u32) ;"));
+		assert!(docs.contains("The first value is returned in `a1`"));
+
+		assert!(HostFn::try_from(parse_quote! {
+			fn range(&mut self, memory: &mut M) -> Result<(u32, u64), TrapReason> {
+				Ok((1, 2))
+			}
+		})
+		.is_err());
+	}
+
+	#[test]
+	fn unsupported_args_are_compile_errors() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn flag(&mut self, memory: &mut M, a: u32, b: bool) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let err = expand_functions(&def, &Default::default()).unwrap_err();
+		assert_eq!(err.to_string(), "Pass by value only supports primitives");
+		assert!(expand_env(&def, &Default::default()).to_string().contains("compile_error !"));
+	}
+
+	#[test]
+	fn writes_memory_is_recorded() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[writes_memory]
+				fn caller(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn is_contract(&mut self, memory: &mut M, ptr: u32) -> Result<u32, TrapReason> {
+					Ok(0)
+				}
+			}
+		});
+
+		let writing = def.host_funcs.iter().map(|f| (f.name.as_str(), f.writes_memory));
+		assert_eq!(writing.collect::<Vec<_>>(), vec![("caller", true), ("is_contract", false)]);
+		let expanded = expand_env(&def, &Default::default()).to_string();
+		assert!(expanded.contains(
+			"fn syscalls_writing_memory () -> & 'static [& 'static [u8]] { & [b\"caller\"] }"
+		));
+	}
+
+	#[test]
+	fn max_output_is_recorded() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[api_version(0)]
+				#[writes_memory]
+				#[max_output(20)]
+				fn caller(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[api_version(0)]
+				#[writes_memory]
+				fn input(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let max_output = def.host_funcs.iter().map(|f| (f.name.as_str(), f.max_output));
+		assert_eq!(max_output.collect::<Vec<_>>(), vec![("caller", Some(20)), ("input", None)]);
+		let expanded = expand_metadata(&def).to_string();
+		assert!(expanded.contains(
+			"fn syscall_max_output (symbol : & [u8]) -> Option < u32 > { \
+			 match symbol { b\"caller\" => Some (20u32) , _ => None , } }"
+		));
+	}
+
+	#[test]
+	fn stable_syscalls_are_sorted() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[api_version(0)]
+				fn origin(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn unstable(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[api_version(0)]
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let expanded = expand_metadata(&def).to_string();
+		assert!(expanded.contains(
+			"const STABLE_SYSCALLS_SORTED : & [& [u8]] = & [b\"caller\" , b\"origin\"] ;"
+		));
+		assert!(expanded.contains("STABLE_SYSCALLS_SORTED . binary_search (& symbol) . is_ok ()"));
+	}
+
+	#[test]
+	fn gas_defaults_to_host_fn_cost() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[gas(RuntimeCosts::Caller)]
+				fn caller(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn input(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let expanded = expand_env(&def, &Default::default()).to_string();
+		assert!(expanded.contains(
+			"let mut costs = [(\"caller\" , RuntimeCosts :: Caller) , \
+			 (\"input\" , RuntimeCosts :: HostFn)] . to_vec () ;"
+		));
+	}
+
+	#[test]
+	fn gas_is_charged_before_arguments_are_decoded() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[gas(RuntimeCosts::Caller)]
+				fn caller(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn input(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let expanded = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(expanded.contains(
+			"{ self . charge_gas (RuntimeCosts :: Caller) ? ; let out_ptr = __a0__ as u32 ;"
+		));
+		assert_eq!(expanded.matches("self . charge_gas (RuntimeCosts :: Caller)").count(), 1);
+		let bench = expand_bench_functions(&def).to_string();
+		assert!(bench.contains("{ self . charge_gas (RuntimeCosts :: Caller) ? ; {"));
+	}
+
+	#[test]
+	fn abi_hash_covers_stable_syscalls_only() {
+		let stable = env_def(parse_quote! {
+			pub mod env {
+				#[api_version(0)]
+				fn caller(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+		let extended = env_def(parse_quote! {
+			pub mod env {
+				#[api_version(0)]
+				fn caller(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn unstable(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[api_version(0)]
+				#[removed]
+				fn gone(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+		let changed = env_def(parse_quote! {
+			pub mod env {
+				#[api_version(0)]
+				fn caller(&mut self, memory: &mut M, out_ptr: u64) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		assert_eq!(stable.abi_hash(), extended.abi_hash());
+		assert_ne!(stable.abi_hash(), changed.abi_hash());
+	}
+
+	#[test]
+	fn aliases_are_dispatched_and_listed() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				/// Returns the caller.
+				#[api_version(0)]
+				#[alias("seal_caller")]
+				#[alias("caller_legacy")]
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let names = def.host_funcs.iter().map(|f| (f.name.as_str(), f.alias_of.as_deref()));
+		assert_eq!(
+			names.collect::<Vec<_>>(),
+			vec![
+				("caller", None),
+				("seal_caller", Some("caller")),
+				("caller_legacy", Some("caller"))
+			],
+		);
+		assert!(def.host_funcs.iter().all(|f| f.api_version == Some(0)));
+		let expanded = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(expanded.contains("2u16 if __is_available__ (Some (0u16))"));
+		let docs = expand_func_doc(&def).to_string();
+		assert!(docs.contains("This is an alias of `caller`."));
+		let bench = expand_bench_functions(&def).to_string();
+		assert!(!bench.contains("bench_seal_caller"));
+
+		let def = EnvDef::try_from(parse_quote! {
+			pub mod env {
+				#[alias("other")]
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn other(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+		assert!(def.err().unwrap().to_string().contains("Duplicate syscall `other`"));
+
+		let item: syn::ItemFn = parse_quote! {
+			#[alias("2legacy")]
+			fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+				Ok(())
+			}
+		};
+		let err = HostFn::try_from(item).err().unwrap();
+		assert_eq!(
+			err.to_string().lines().last(),
+			Some("#[alias] must not start with a digit or be a Rust keyword")
+		);
+	}
+
+	#[test]
+	fn stable_syscalls_need_docs() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				/// Returns the caller.
+				#[api_version(0)]
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn noop(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+		let relaxed = EnvConfig { deny_undocumented: false, ..Default::default() };
+		assert!(!expand_env(&def, &relaxed).to_string().contains("compile_error"));
+		let strict = EnvConfig { deny_undocumented: true, ..Default::default() };
+		assert!(expand_env(&def, &strict)
+			.to_string()
+			.contains("Host function `noop` is missing a doc comment"));
+
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[api_version(0)]
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+		assert!(expand_env(&def, &relaxed)
+			.to_string()
+			.contains("Host function `caller` is missing a doc comment"));
+	}
+
+	#[test]
+	fn mock_functions_are_test_only() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[symbol = "seal0_caller"]
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[removed]
+				fn gone(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let expanded = expand_mock_functions(&def).to_string();
+		assert!(expanded
+			.contains("# [cfg (test)] # [allow (dead_code)] pub fn mock_seal0_caller < E : Ext"));
+		assert!(expanded
+			.contains(". handle_ecall (memory , b\"seal0_caller\" , ApiVersion :: UnsafeNewest)"));
+		assert!(!expanded.contains("mock_gone"));
+	}
+
+	#[test]
+	fn bench_only_functions_are_not_dispatched() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[bench_only]
+				fn set_up(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		assert_eq!(def.host_funcs.len(), 1);
+		assert_eq!(def.bench_only.len(), 1);
+		let expanded = expand_env(&def, &Default::default()).to_string();
+		assert!(!expanded.contains("b\"set_up\""));
+		assert!(expanded.contains("pub fn bench_set_up"));
+		assert!(expanded
+			.contains("pub const BENCH_SYSCALLS : & [& str] = & [\"caller\" , \"set_up\"] ;"));
+	}
+
+	#[test]
+	fn bench_syscalls_skip_removed() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[removed]
+				fn gone(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[cfg(feature = "runtime-benchmarks")]
+				fn noop(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let expanded = expand_env(&def, &Default::default()).to_string();
+		assert!(expanded.contains(
+			"pub const BENCH_SYSCALLS : & [& str] = & [\"caller\" , \
+			 # [cfg (feature = \"runtime-benchmarks\")] \"noop\"] ;"
+		));
+		assert!(expanded.contains("pub fn symbol_to_index (symbol : & str) -> Option < usize >"));
+	}
+
+	#[test]
+	fn required_costs_skip_aliases() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[alias("seal_caller")]
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[removed]
+				fn gone(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[bench_only]
+				fn set_up(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let expanded = expand_env(&def, &Default::default()).to_string();
+		assert!(expanded.contains("pub const REQUIRED_COSTS : & [& str] = & [\"caller\"] ;"));
+	}
+
+	#[test]
+	fn register_layout_is_documented() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn put(&mut self, memory: &mut M, key_ptr: u32, value: u64, flags: u8) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[max_regs(2)]
+				fn spilled(&mut self, memory: &mut M, a: u32, b: u32, c: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn none(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let layout = def.host_funcs.iter().map(HostFn::register_layout).collect::<Vec<_>>();
+		assert_eq!(
+			layout,
+			vec![
+				vec!["- `a0`: `key_ptr`", "- `a1`..`a2`: `value`", "- `a3`: `flags`"],
+				vec!["- `a0`: pointer to all arguments packed in guest memory"],
+				vec![],
+			],
+		);
+		let docs = expand_func_doc(&def).to_string();
+		assert!(docs.contains(
+			r#"# [doc = "\n# Registers\n- `a0`: `key_ptr`\n- `a1`..`a2`: `value`\n- `a3`: `flags`"]"#
+		));
+		assert_eq!(docs.matches("# Registers").count(), 2);
+		assert!(docs.contains(
+			"in the following order:\\n- `a`: `u32` at offset 0\\n- `b`: `u32` at offset 4\\n\
+			 - `c`: `u32` at offset 8"
+		));
+		assert_eq!(docs.matches("# Arguments passed in memory").count(), 1);
+	}
+
+	#[test]
+	fn u128_is_passed_by_reference() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn transfer(&mut self, memory: &mut M, to_ptr: u32, value: u128) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[max_regs(1)]
+				fn spilled(&mut self, memory: &mut M, to_ptr: u32, value: u128) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		assert_eq!(def.host_funcs[0].registers_used(), 2);
+		let expanded = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(expanded.contains("let value : u128 = memory . read_as (__a1__) ? ;"));
+		assert!(expanded.contains(
+			"debug_assert_eq ! (< (u32 , u128 ,) as MaxEncodedLen > :: max_encoded_len () , 20usize"
+		));
+		let docs = expand_func_doc(&def).to_string();
+		let note = "`value` of type `u128` is passed as a pointer to its little endian encoding";
+		assert_eq!(docs.matches(note).count(), 1);
+	}
+
+	#[test]
+	fn byte_array_is_passed_by_reference() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn transfer(&mut self, memory: &mut M, to: [u8; 20], value: u64) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[max_regs(2)]
+				fn spilled(&mut self, memory: &mut M, to: [u8; 20], value: u64) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		assert_eq!(def.host_funcs[0].registers_used(), 3);
+		assert_eq!(def.host_funcs[0].register_layout()[0], "- `a0`: `to`");
+		let expanded = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(expanded.contains("let to : [u8 ; 20] = memory . read_array (__a0__) ? ;"));
+		assert!(expanded.contains(
+			"debug_assert_eq ! (< ([u8 ; 20] , u64 ,) as MaxEncodedLen > :: max_encoded_len () , \
+			 28usize"
+		));
+		let docs = expand_func_doc(&def).to_string();
+		let note = "`to` is passed as a pointer to the bytes in guest memory.";
+		assert_eq!(docs.matches(note).count(), 1);
+	}
+
+	#[test]
+	fn arguments_in_memory_are_packed() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn seven(
+					&mut self,
+					memory: &mut M,
+					a: u32,
+					b: u32,
+					c: u32,
+					d: u32,
+					e: u32,
+					f: u32,
+					g: u32,
+				) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn mixed(&mut self, memory: &mut M, a: u8, b: i16, c: u64, d: u64, e: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let expanded = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(expanded.contains(
+			"debug_assert_eq ! (< (u32 , u32 , u32 , u32 , u32 , u32 , u32 ,) as MaxEncodedLen > \
+			 :: max_encoded_len () , 28usize"
+		));
+		assert!(expanded.contains(
+			"debug_assert_eq ! (< (u8 , i16 , u64 , u64 , u32 ,) as MaxEncodedLen > \
+			 :: max_encoded_len () , 23usize"
+		));
+	}
+
+	#[test]
+	fn max_regs_forces_arguments_into_memory() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[max_regs(1)]
+				fn pair(&mut self, memory: &mut M, a: u32, b: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		assert_eq!(def.host_funcs[0].max_regs, 1);
+		assert_eq!(def.host_funcs[0].registers_used(), 1);
+		let expanded = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(expanded.contains("memory . read_as (__a0__)"));
+
+		let item: syn::ItemFn = parse_quote! {
+			#[max_regs(7)]
+			fn pair(&mut self, memory: &mut M, a: u32, b: u32) -> Result<(), TrapReason> {
+				Ok(())
+			}
+		};
+		let err = HostFn::try_from(item).err().unwrap();
+		assert!(err.to_string().ends_with("#[max_regs] can be at most 6"));
+	}
+
+	#[test]
+	fn duplicate_syscalls_are_rejected() {
+		let duplicate: syn::ItemMod = parse_quote! {
+			pub mod env {
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[cfg(feature = "runtime-benchmarks")]
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		};
+		let err = EnvDef::try_from(duplicate).err().unwrap();
+		assert_eq!(err.to_string(), "Duplicate syscall `caller`");
+
+		env_def(parse_quote! {
+			pub mod env {
+				#[cfg(feature = "runtime-benchmarks")]
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[cfg(not(feature = "runtime-benchmarks"))]
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+	}
+
+	#[test]
+	fn versions_are_exported_under_their_own_symbol() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn call(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[version(1)]
+				fn call(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let names = def.host_funcs.iter().map(|f| (f.name.as_str(), f.version));
+		assert_eq!(names.collect::<Vec<_>>(), vec![("call", 0), ("seal1_call", 1)]);
+		let expanded = expand_env(&def, &Default::default()).to_string();
+		assert!(expanded.contains(
+			"match base_name { \"call\" => Some (b\"seal1_call\") , \"call\" => Some (b\"call\") ,"
+		));
+		assert!(expanded.contains("fn bench_seal1_call"));
+		assert!(expand_metadata(&def)
+			.to_string()
+			.contains("& [(b\"call\" , 0u8) , (b\"seal1_call\" , 1u8)]"));
+
+		let item: syn::ItemFn = parse_quote! {
+			#[version(1)]
+			#[version(2)]
+			fn call(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+				Ok(())
+			}
+		};
+		let err = HostFn::try_from(item).err().unwrap();
+		assert!(err.to_string().ends_with("#[version] can only be specified once"));
+	}
+
+	#[test]
+	fn syscall_enum_has_pascal_cased_variants() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn get_storage(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[version(1)]
+				#[cfg(feature = "runtime-benchmarks")]
+				fn get_storage(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let variants = def.host_funcs.iter().map(|f| f.variant().to_string());
+		assert_eq!(variants.collect::<Vec<_>>(), vec!["GetStorage", "Seal1GetStorage"]);
+		let expanded = expand_syscall_enum(&def).unwrap().to_string();
+		assert!(expanded.contains(
+			"# [cfg (feature = \"runtime-benchmarks\")] Self :: Seal1GetStorage => b\"seal1_get_storage\" ,"
+		));
+
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn get_storage(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[symbol = "get__storage"]
+				fn get_storage_raw(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+		let err = expand_syscall_enum(&def).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"Syscalls `get_storage` and `get__storage` map to the same variant `GetStorage`"
+		);
+
+		// A variant can't be derived from a symbol starting with a digit, which must be reported
+		// as a compile error instead of panicking while expanding.
+		let def = EnvDef::try_from(parse_quote! {
+			pub mod env {
+				#[symbol = "1get_storage"]
+				fn get_storage(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+		let err = def.err().unwrap().to_compile_error().to_string();
+		assert!(err.contains("compile_error !"));
+		assert!(err.contains("#[symbol] must not start with a digit or be a Rust keyword"));
+	}
+
+	#[test]
+	fn category_is_documented_and_in_manifest() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[category = "storage"]
+				fn get_storage(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[category = "say \"hi\""]
+				fn greet(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let docs = expand_func_doc(&def).to_string();
+		assert!(docs.contains(r#"# [doc = "\n**Category:** storage"]"#));
+		assert!(docs.contains(r#"# [doc = "\n**Category:** say \"hi\""]"#));
+		assert_eq!(
+			def.manifest(),
+			r#"[{"name":"get_storage","stable":false,"arity":0,"args":[],"returns":"()","#
+				.to_owned() + r#""category":"storage"},"# +
+				r#"{"name":"greet","stable":false,"arity":0,"args":[],"returns":"()","# +
+				r#""category":"say \"hi\""}]"#,
+		);
+
+		let err = HostFn::try_from(parse_quote! {
+			#[category = "storage"]
+			#[category = "crypto"]
+			fn get_storage(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+				Ok(())
+			}
+		})
+		.err()
+		.unwrap();
+		assert!(err.to_string().ends_with("#[category] can only be specified once"));
+	}
+
+	#[test]
+	fn manifest_describes_callable_syscalls() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[api_version(0)]
+				fn caller(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn offset(&mut self, memory: &mut M, a: i64, b: u32) -> Result<i32, TrapReason> {
+					Ok(0)
+				}
+
+				#[removed]
+				fn gone(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[cfg(feature = "runtime-benchmarks")]
+				fn noop(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		assert_eq!(
+			def.manifest(),
+			r#"[{"name":"caller","stable":true,"arity":1,"args":["u32"],"returns":"()"},"#
+				.to_owned() + r#"{"name":"offset","stable":false,"arity":2,"args":["i64","u32"],"returns":"i32"}]"#,
+		);
+	}
+
+	#[test]
+	fn markdown_table_lists_callable_syscalls() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[api_version(0)]
+				fn caller(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn offset(&mut self, memory: &mut M, a: i64, b: u32) -> Result<i32, TrapReason> {
+					Ok(0)
+				}
+
+				#[removed]
+				fn gone(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		assert_eq!(
+			def.markdown_table(),
+			"| name | args | return | stable |\n\
+			 |---|---|---|---|\n\
+			 | `caller` | `out_ptr: u32` | `()` | yes |\n\
+			 | `offset` | `a: i64`, `b: u32` | `i32` | no |\n",
+		);
+	}
+
+	#[test]
+	fn symbol_overrides_the_function_name() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[symbol = "seal.caller"]
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		assert_eq!(def.host_funcs[0].name, "seal.caller");
+		let expanded = expand_env(&def, &Default::default()).to_string();
+		assert!(expanded.contains("pub const SYSCALL_NAMES : & [& str] = & [\"seal.caller\"] ;"));
+		assert!(expanded.contains("fn bench_seal_caller"));
+
+		let item: syn::ItemFn = parse_quote! {
+			#[symbol = ""]
+			fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+				Ok(())
+			}
+		};
+		let err = HostFn::try_from(item).err().unwrap();
+		assert_eq!(
+			err.to_string().lines().last(),
+			Some("#[symbol] must be a non-empty ASCII string")
+		);
+
+		for symbol in ["1foo", "fn", "-"] {
+			let item: syn::ItemFn = parse_quote! {
+				#[symbol = #symbol]
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			};
+			let err = HostFn::try_from(item).err().unwrap();
+			assert_eq!(
+				err.to_string().lines().last(),
+				Some("#[symbol] must not start with a digit or be a Rust keyword"),
+				"{symbol}",
+			);
+		}
+	}
+
+	#[test]
+	fn syscall_ids_are_independent_of_cfg() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn value_transferred(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[cfg(feature = "runtime-benchmarks")]
+				fn noop(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn caller(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let ids = ["caller", "noop", "value_transferred"].map(|name| def.syscall_id(name));
+		assert_eq!(ids, [0, 1, 2]);
+		assert_eq!(def.check_syscall_count(), Ok(()));
+		let by_id = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(by_id.contains("match __syscall_id__ . 0 { 2u16 if __is_available__ (None)"));
+		assert!(by_id.contains("# [cfg (feature = \"runtime-benchmarks\")] 1u16 if"));
+
+		// The byte-slice entry point dispatches on the id, too.
+		let expanded = expand_env(&def, &Default::default()).to_string();
+		assert!(expanded
+			.contains("let Some (__syscall_id__) = resolve_syscall_id (__syscall_symbol__) else"));
+		assert!(expanded.contains(
+			"self . handle_ecall_by_id (memory , __syscall_id__ , __available_api_version__)"
+		));
+	}
+
+	#[test]
+	fn syscall_ids_must_fit_into_u16() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn noop(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+		let host_funcs = (0..=u32::from(u16::MAX))
+			.map(|idx| HostFn { name: format!("noop{idx}"), ..def.host_funcs[0].clone() })
+			.collect();
+
+		let def = EnvDef { host_funcs, ..def };
+		assert!(def.check_syscall_count().is_err());
+		let def = EnvDef { host_funcs: def.host_funcs[1..].to_vec(), ..def };
+		assert_eq!(def.check_syscall_count(), Ok(()));
+		assert_eq!(def.syscall_id("noop9999"), u16::MAX - 1);
+	}
+
+	#[test]
+	fn cfg_can_be_repeated() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[cfg(feature = "a")]
+				#[cfg(target_pointer_width = "64")]
+				fn wide(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[cfg(feature = "a")]
+				fn wide(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		assert_eq!(def.host_funcs[0].cfg.len(), 2);
+		let cfg = "# [cfg (feature = \"a\")] # [cfg (target_pointer_width = \"64\")]";
+		let expanded = expand_env(&def, &Default::default()).to_string();
+		assert!(expanded.contains(&format!("{cfg} pub fn bench_wide")));
+		let expanded = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(expanded.contains(&format!("{cfg} 0u16 if __is_available__")));
+
+		let err = EnvDef::try_from(parse_quote! {
+			pub mod env {
+				#[cfg(feature = "a")]
+				#[cfg(feature = "b")]
+				fn wide(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[cfg(feature = "a")]
+				#[cfg(feature = "b")]
+				fn wide(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		})
+		.err()
+		.unwrap();
+		assert_eq!(err.to_string(), "Duplicate syscall `wide`");
+	}
+
+	#[test]
+	fn registers_used_are_derived_from_arguments() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn one(&mut self, memory: &mut M, a: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn wide(&mut self, memory: &mut M, a: u64, b: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn spilled(
+					&mut self,
+					memory: &mut M,
+					a: u64,
+					b: u64,
+					c: u64,
+				) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let used = def.host_funcs.iter().map(HostFn::registers_used).collect::<Vec<_>>();
+		assert_eq!(used, vec![1, 3, 6]);
+	}
+
+	#[test]
+	fn verifier_only_emits_metadata_only() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				/// Returns the caller.
+				#[api_version(0)]
+				fn caller(&mut self, memory: &mut M, out_ptr: u32) -> Result<u32, TrapReason> {
+					Ok(0)
+				}
+			}
+		});
+		assert_eq!(def.host_funcs[0].signature(), "fn(u32) -> u32");
+
+		let config = EnvConfig { verifier_only: true, ..Default::default() };
+		let expanded = expand_env(&def, &config).to_string();
+		assert!(expanded.contains("fn list_syscalls"));
+		assert!(expanded.contains("fn syscall_arity"));
+		assert!(expanded.contains("fn expected_signature"));
+		assert!(!expanded.contains("fn handle_ecall"));
+
+		let config = EnvConfig { verifier_only: false, ..Default::default() };
+		assert!(expand_env(&def, &config).to_string().contains("fn handle_ecall"));
+	}
+
+	#[test]
+	fn docs_only_skips_dispatch() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				/// Returns the caller.
+				#[api_version(0)]
+				fn caller(&mut self, memory: &mut M, out_ptr: u32) -> Result<u32, TrapReason> {
+					Ok(0)
+				}
+			}
+		});
+
+		let config: EnvConfig = parse_quote!(docs_only);
+		assert!(config.docs_only);
+		let config = EnvConfig { verifier_only: false, ..config };
+		let expanded = expand_env(&def, &config).to_string();
+		assert!(expanded.contains("fn list_syscalls"));
+		assert!(expanded.contains("fn syscalls_by_tier"));
+		assert!(expanded.contains("SYSCALL_MANIFEST"));
+		assert!(expanded.contains("trait SyscallDoc"));
+		assert!(!expanded.contains("fn handle_ecall"));
+		assert!(!expanded.contains("fn bench_caller"));
+	}
+
+	#[test]
+	fn host_trace_can_be_disabled() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn noop(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let traced = EnvConfig { host_trace: true, ..Default::default() };
+		let expanded = expand_functions(&def, &traced).unwrap().to_string();
+		assert!(expanded.contains("runtime::revive::strace"));
+
+		let untraced = EnvConfig { host_trace: false, ..Default::default() };
+		let expanded = expand_functions(&def, &untraced).unwrap().to_string();
+		assert!(!expanded.contains("runtime::revive::strace"));
+		assert!(expanded.contains("(|| { Ok (()) }) ()"));
+	}
+
+	#[test]
+	fn array_is_written_to_out_ptr() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn hash(&mut self, memory: &mut M, in_ptr: u32, out_ptr: u32) -> Result<[u8; 32], TrapReason> {
+					Ok([0; 32])
+				}
+			}
+		});
+
+		let f = &def.host_funcs[0];
+		assert!(matches!(f.returns, HostFnReturn::Array(32)));
+		assert!(f.writes_memory);
+		assert_eq!(f.signature(), "fn(u32, u32)");
+		let docs = expand_func_doc(&def).to_string();
+		assert!(docs.contains("Writes the resulting `[u8; 32]` to the guest memory at `out_ptr`."));
+		assert!(docs.contains("fn hash (in_ptr : u32 , out_ptr : u32) ;"));
+		let config = EnvConfig { host_trace: false, ..Default::default() };
+		let expanded = expand_functions(&def, &config).unwrap().to_string();
+		assert!(expanded.contains(
+			"let __output__ : Result < [u8 ; 32] , TrapReason > = { (|| { Ok ([0 ; 32]) }) () } ; \
+			 memory . write (out_ptr , & __output__ ?) ? ; Ok (())"
+		));
+
+		for item in [
+			parse_quote! {
+				fn hash(&mut self, memory: &mut M, out_ptr: u32, in_ptr: u32) -> Result<[u8; 32], TrapReason> {
+					Ok([0; 32])
+				}
+			},
+			parse_quote! {
+				fn hash(&mut self, memory: &mut M, out_ptr: u64) -> Result<[u8; 32], TrapReason> {
+					Ok([0; 32])
+				}
+			},
+		] {
+			let err = HostFn::try_from(item).err().unwrap();
+			assert!(err.to_string().ends_with("must take `out_ptr: u32` as their last argument"));
+		}
+		assert!(HostFn::try_from(parse_quote! {
+			fn hash(&mut self, memory: &mut M, out_ptr: u32) -> Result<[u32; 8], TrapReason> {
+				Ok([0; 8])
+			}
+		})
+		.is_err());
+	}
+
+	#[test]
+	fn return_code_enum_is_converted() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn lookup(&mut self, memory: &mut M, key_ptr: u32) -> Result<LookupResult, TrapReason> {
+					Ok(LookupResult::NotFound)
+				}
+			}
+		});
+
+		let f = &def.host_funcs[0];
+		assert!(matches!(f.returns, HostFnReturn::ReturnCodeEnum(_)));
+		assert_eq!(f.signature(), "fn(u32) -> u32");
+		let docs = expand_func_doc(&def).to_string();
+		assert!(docs.contains("fn lookup (key_ptr : u32) -> ReturnErrorCode ;"));
+		let config = EnvConfig { host_trace: false, ..Default::default() };
+		let expanded = expand_functions(&def, &config).unwrap().to_string();
+		assert!(expanded.contains(
+			"| ret_code : LookupResult | { Some (u32 :: from (< LookupResult as Into < \
+			 ReturnErrorCode >> :: into (ret_code)) . into ()) }"
+		));
+
+		let path: HostFn = HostFn::try_from(parse_quote! {
+			fn lookup(&mut self, memory: &mut M) -> Result<crate::LookupResult, TrapReason> {
+				Ok(LookupResult::NotFound)
+			}
+		})
+		.unwrap();
+		assert!(matches!(path.returns, HostFnReturn::ReturnCodeEnum(_)));
+
+		let rejected: [syn::Type; 5] = [
+			parse_quote!(u128),
+			parse_quote!(bool),
+			parse_quote!(Option<u32>),
+			parse_quote!(&'static str),
+			parse_quote!((u32, u64)),
+		];
+		for ty in rejected {
+			let Err(e) = HostFn::try_from(parse_quote! {
+				fn lookup(&mut self, memory: &mut M) -> Result<#ty, TrapReason> {
+					unreachable!()
+				}
+			}) else {
+				panic!("`{}` must be rejected", ty.to_token_stream());
+			};
+			assert!(e.to_string().contains("Result<E, TrapReason> where E is an enum"));
+		}
+	}
+
+	#[test]
+	fn bodies_can_be_lifted_into_wrappers() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn add(&mut self, memory: &mut M, a: u32, b: u32) -> Result<u32, TrapReason> {
+					Ok(a + b)
+				}
+			}
+		});
+
+		let config = EnvConfig { host_trace: false, syscall_wrappers: true, ..Default::default() };
+		let expanded = expand_functions(&def, &config).unwrap().to_string();
+		assert!(expanded.contains("(|| { self . syscall_add (memory , a , b) }) ()"));
+		assert!(!expanded.contains("Ok (a + b)"));
+		let expanded = expand_env(&def, &config).to_string();
+		assert!(expanded.contains(
+			"# [inline (always)] fn syscall_add (& mut self , memory : & mut M , a : u32 , b : u32) \
+			 -> Result < u32 , TrapReason > { Ok (a + b) }"
+		));
+
+		let config = EnvConfig { syscall_wrappers: false, ..config };
+		let expanded = expand_env(&def, &config).to_string();
+		assert!(!expanded.contains("fn syscall_add"));
+	}
+
+	#[test]
+	fn host_time_is_recorded() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn add(&mut self, memory: &mut M, a: u32, b: u32) -> Result<u32, TrapReason> {
+					Ok(a + b)
+				}
+			}
+		});
+
+		let config = EnvConfig { host_timing: true, ..Default::default() };
+		let expanded = expand_functions(&def, &config).unwrap().to_string();
+		assert!(expanded.contains(
+			"let result = { let __started__ = std :: time :: Instant :: now () ; let __result__ = \
+			 (|| { Ok (a + b) }) () ; self . ext () . record_host_time (\"add\" , __started__ . \
+			 elapsed ()) ; __result__ } ;"
+		));
+
+		let config = EnvConfig { host_timing: false, ..config };
+		let expanded = expand_functions(&def, &config).unwrap().to_string();
+		assert!(!expanded.contains("record_host_time"));
+	}
+
+	#[test]
+	fn unknown_symbol_is_traced() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				fn noop(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let traced = EnvConfig { host_trace: true, ..Default::default() };
+		let expanded = expand_env(&def, &traced).to_string();
+		assert!(expanded.contains(
+			"\"unknown syscall {}\\n\" , alloc :: string :: String :: from_utf8_lossy \
+			 (__syscall_symbol__) ,"
+		));
+		assert!(expanded.contains("\"unknown syscall id {}\\n\" , __syscall_id__ . 0"));
+
+		let untraced = EnvConfig { host_trace: false, ..Default::default() };
+		let expanded = expand_env(&def, &untraced).to_string();
+		assert!(!expanded.contains("unknown syscall"));
+	}
+
+	#[test]
+	fn structured_trace_passes_arguments() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[no_trace(key_ptr)]
+				fn sign(&mut self, memory: &mut M, key_ptr: u32, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let config = EnvConfig { host_trace: true, structured_trace: true, ..Default::default() };
+		let expanded = expand_functions(&def, &config).unwrap().to_string();
+		assert!(expanded.contains(
+			"self . ext () . trace_syscall (\"sign\" , & [(\"key_ptr\" , & format_args ! \
+			 (\"<redacted>\") as & dyn core :: fmt :: Debug) , (\"out_ptr\" , & out_ptr as & dyn \
+			 core :: fmt :: Debug)] , & result) ;"
+		));
+		assert!(!expanded.contains("append_debug_buffer"));
+	}
+
+	#[test]
+	fn no_trace_args_are_redacted() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[no_trace(key_ptr)]
+				fn sign(&mut self, memory: &mut M, key_ptr: u32, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let (fmt, args) = def.host_funcs[0].trace_fmt();
+		assert_eq!(fmt, "sign(key_ptr: <redacted>, out_ptr: {:?}) = {:?}\n");
+		assert_eq!(args, vec![Ident::new("out_ptr", Span::call_site())]);
+
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[symbol = "seal{0}"]
+				fn sign(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+		let (fmt, _) = def.host_funcs[0].trace_fmt();
+		assert_eq!(fmt, "seal{{0}}(out_ptr: {:?}) = {:?}\n");
+
+		let item: syn::ItemFn = parse_quote! {
+			#[no_trace(unknown)]
+			fn sign(&mut self, memory: &mut M, key_ptr: u32) -> Result<(), TrapReason> {
+				Ok(())
+			}
+		};
+		assert!(HostFn::try_from(item).is_err());
+	}
+
+	#[test]
+	fn slices_are_read_from_memory() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[slice(ptr = key_ptr, len = key_len)]
+				fn contains(&mut self, memory: &M, key_ptr: u32, key_len: u32) -> Result<u32, TrapReason> {
+					Ok(key.len() as u32)
+				}
+			}
+		});
+
+		let body = def.host_funcs[0].item.block.to_token_stream().to_string();
+		assert!(body.starts_with(
+			"{ self . charge_gas (crate :: wasm :: RuntimeCosts :: CopyFromContract (key_len)) ? ; \
+			 let key = memory . read (key_ptr , key_len) ? ; let key : & [u8] = & key ;"
+		));
+		let docs = expand_func_doc(&def).to_string();
+		assert!(docs.contains("fn contains (key : & [u8]) -> u32 ;"));
+		assert!(docs.contains("`key` is passed as a pointer `key_ptr`"));
+		assert_eq!(def.host_funcs[0].signature(), "fn(u32, u32) -> u32");
+
+		let item: syn::ItemFn = parse_quote! {
+			#[slice(ptr = key_ptr, len = len)]
+			fn contains(&mut self, memory: &M, key_ptr: u32) -> Result<(), TrapReason> {
+				Ok(())
+			}
+		};
+		assert!(HostFn::try_from(item).is_err());
+
+		let item: syn::ItemFn = parse_quote! {
+			#[slice(ptr = key, len = key_len)]
+			fn contains(&mut self, memory: &M, key: u32, key_len: u32) -> Result<(), TrapReason> {
+				Ok(())
+			}
+		};
+		assert!(HostFn::try_from(item).is_err());
+	}
+
+	#[test]
+	fn tiers_are_derived_from_attributes() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[api_version(0)]
+				fn stable(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[api_version(0)]
+				#[deprecated]
+				fn deprecated(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				fn unstable(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+
+				#[removed]
+				fn removed(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		let tiers = def.host_funcs.iter().map(|f| (f.name.as_str(), f.tier())).collect::<Vec<_>>();
+		assert_eq!(
+			tiers,
+			vec![
+				("stable", Tier::Stable),
+				("deprecated", Tier::Deprecated),
+				("unstable", Tier::Unstable),
+				("removed", Tier::Removed),
+			],
+		);
+	}
+
+	#[test]
+	fn deprecated_functions_warn_but_stay_callable() {
+		let def = env_def(parse_quote! {
+			pub mod env {
+				#[api_version(0)]
+				#[deprecated = "use seal2_foo {}"]
+				fn foo(&mut self, memory: &mut M) -> Result<(), TrapReason> {
+					Ok(())
+				}
+			}
+		});
+
+		assert_eq!(def.host_funcs[0].deprecated.as_deref(), Some("use seal2_foo {}"));
+		assert!(def.host_funcs[0].is_callable(false));
+		let expanded = expand_functions(&def, &Default::default()).unwrap().to_string();
+		assert!(expanded.contains("if self . ext () . note_deprecated_syscall (\"foo\") {"));
+		// The note is passed as an argument so that braces in it aren't taken as placeholders.
+		assert!(expanded.contains(
+			"target : \"runtime::revive\" , \"{}\" , \"syscall `foo` is deprecated: use seal2_foo {}\""
+		));
+		assert!(expanded.contains("\"syscall `foo` is deprecated: use seal2_foo {}\\n\""));
+		let docs = expand_func_doc(&def).to_string();
+		assert!(docs.contains("This API is deprecated and will be removed.\\n\\nuse seal2_foo {}"));
+	}
+}
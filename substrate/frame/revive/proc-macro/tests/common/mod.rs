@@ -0,0 +1,257 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A minimal stand-in for the runtime of pallet-revive to expand `#[define_env]` against.
+//!
+//! It only has what the generated code needs and what the tests observe.
+
+#![allow(dead_code)]
+
+use core::marker::PhantomData;
+
+pub trait Config {}
+pub struct Test;
+impl Config for Test {}
+
+#[derive(Debug)]
+pub struct DispatchError;
+
+pub enum Error<T> {
+	InvalidSyscall,
+	DecodingFailed,
+	StateChangeDenied,
+	_Phantom(PhantomData<T>),
+}
+
+impl<T> From<Error<T>> for DispatchError {
+	fn from(_: Error<T>) -> Self {
+		DispatchError
+	}
+}
+
+#[derive(Debug)]
+pub enum TrapReason {
+	SupervisorError(DispatchError),
+}
+
+impl<T: Into<DispatchError>> From<T> for TrapReason {
+	fn from(from: T) -> Self {
+		Self::SupervisorError(from.into())
+	}
+}
+
+#[derive(Clone, Copy)]
+pub enum ApiVersion {
+	UnsafeNewest,
+	Versioned(u16),
+}
+
+pub mod sp_std {
+	#[derive(Default)]
+	pub struct Writer(Vec<u8>);
+
+	impl core::fmt::Write for Writer {
+		fn write_str(&mut self, s: &str) -> core::fmt::Result {
+			self.0.extend_from_slice(s.as_bytes());
+			Ok(())
+		}
+	}
+
+	impl Writer {
+		pub fn inner(&self) -> &Vec<u8> {
+			&self.0
+		}
+	}
+}
+
+pub mod wasm {
+	#[derive(Clone, Copy, Debug, PartialEq)]
+	pub enum RuntimeCosts {
+		HostFn,
+	}
+}
+pub use wasm::RuntimeCosts;
+
+pub struct Weight(u64);
+
+impl Weight {
+	pub fn ref_time(&self) -> u64 {
+		self.0
+	}
+}
+
+pub trait Token<T: Config> {
+	fn weight(&self) -> Weight;
+}
+
+impl<T: Config> Token<T> for RuntimeCosts {
+	fn weight(&self) -> Weight {
+		Weight(1)
+	}
+}
+
+#[derive(Default)]
+pub struct GasMeter;
+
+impl GasMeter {
+	pub fn sync_from_executor(&mut self, _: u64) -> Result<u64, DispatchError> {
+		Ok(0)
+	}
+
+	pub fn sync_to_executor(&mut self, _: u64) -> Result<u64, DispatchError> {
+		Ok(0)
+	}
+}
+
+pub struct ReadOnlyScope {
+	was_read_only: bool,
+}
+
+pub trait Ext {
+	type T: Config;
+	fn gas_meter_mut(&mut self) -> &mut GasMeter;
+	fn is_read_only(&self) -> bool;
+	fn enter_read_only(&mut self) -> ReadOnlyScope;
+	fn exit_read_only(&mut self, scope: ReadOnlyScope);
+	fn set_storage(&mut self, value: u32) -> Result<(), DispatchError>;
+	fn append_debug_buffer(&mut self, msg: &str) -> bool;
+	fn note_deprecated_syscall(&mut self, name: &'static str) -> bool;
+	fn trace_syscall(
+		&self,
+		name: &str,
+		args: &[(&str, &dyn core::fmt::Debug)],
+		result: &dyn core::fmt::Debug,
+	);
+	fn record_host_time(&self, name: &str, elapsed: core::time::Duration);
+}
+
+#[derive(Default)]
+pub struct MockExt {
+	pub gas_meter: GasMeter,
+	pub read_only: bool,
+	pub storage: Option<u32>,
+	pub deprecated_syscalls: Vec<&'static str>,
+}
+
+impl Ext for MockExt {
+	type T = Test;
+
+	fn gas_meter_mut(&mut self) -> &mut GasMeter {
+		&mut self.gas_meter
+	}
+
+	fn is_read_only(&self) -> bool {
+		self.read_only
+	}
+
+	fn enter_read_only(&mut self) -> ReadOnlyScope {
+		ReadOnlyScope { was_read_only: core::mem::replace(&mut self.read_only, true) }
+	}
+
+	fn exit_read_only(&mut self, scope: ReadOnlyScope) {
+		self.read_only = scope.was_read_only;
+	}
+
+	fn set_storage(&mut self, value: u32) -> Result<(), DispatchError> {
+		if self.read_only {
+			return Err(Error::<Test>::StateChangeDenied.into())
+		}
+		self.storage = Some(value);
+		Ok(())
+	}
+
+	fn append_debug_buffer(&mut self, _msg: &str) -> bool {
+		false
+	}
+
+	fn note_deprecated_syscall(&mut self, name: &'static str) -> bool {
+		if self.deprecated_syscalls.contains(&name) {
+			return false
+		}
+		self.deprecated_syscalls.push(name);
+		true
+	}
+
+	fn trace_syscall(
+		&self,
+		_name: &str,
+		_args: &[(&str, &dyn core::fmt::Debug)],
+		_result: &dyn core::fmt::Debug,
+	) {
+	}
+
+	fn record_host_time(&self, _name: &str, _elapsed: core::time::Duration) {}
+}
+
+pub trait Memory<T: Config> {
+	fn write(&mut self, ptr: u32, buf: &[u8]) -> Result<(), DispatchError>;
+}
+
+pub trait PolkaVmInstance<T: Config>: Memory<T> {
+	fn gas(&self) -> u64;
+	fn set_gas(&mut self, gas: u64);
+	fn read_input_regs(&self) -> (u32, u32, u32, u32, u32, u32);
+	fn write_output(&mut self, output: u64);
+}
+
+#[derive(Default)]
+pub struct MockMemory {
+	pub regs: [u32; 6],
+	pub output: Option<u64>,
+}
+
+impl Memory<Test> for MockMemory {
+	fn write(&mut self, _ptr: u32, _buf: &[u8]) -> Result<(), DispatchError> {
+		Ok(())
+	}
+}
+
+impl PolkaVmInstance<Test> for MockMemory {
+	fn gas(&self) -> u64 {
+		0
+	}
+
+	fn set_gas(&mut self, _gas: u64) {}
+
+	fn read_input_regs(&self) -> (u32, u32, u32, u32, u32, u32) {
+		let [a0, a1, a2, a3, a4, a5] = self.regs;
+		(a0, a1, a2, a3, a4, a5)
+	}
+
+	fn write_output(&mut self, output: u64) {
+		self.output = Some(output);
+	}
+}
+
+pub struct Runtime<'a, E: Ext, M: ?Sized> {
+	pub ext: &'a mut E,
+	_phantom_data: PhantomData<M>,
+}
+
+impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
+	pub fn new(ext: &'a mut E, _input_data: Vec<u8>) -> Self {
+		Self { ext, _phantom_data: PhantomData }
+	}
+
+	pub fn ext(&mut self) -> &mut E {
+		self.ext
+	}
+
+	pub fn charge_gas(&mut self, _costs: RuntimeCosts) -> Result<(), DispatchError> {
+		Ok(())
+	}
+}
@@ -0,0 +1,84 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Calls `#[read_only]` host functions through the generated dispatch code.
+//!
+//! The real environment has no such host function, so this expands `#[define_env]` against a
+//! minimal stand-in for the pallet's runtime.
+
+#![cfg(not(feature = "verifier-only"))]
+#![allow(unexpected_cfgs)]
+
+extern crate alloc;
+
+mod common;
+
+use common::*;
+use pallet_revive_proc_macro::define_env;
+
+#[define_env]
+pub mod env {
+	/// Stores `value` unless `value` is zero, then fails after storing.
+	#[api_version(0)]
+	#[read_only]
+	fn store(&mut self, _memory: &mut M, value: u32) -> Result<u32, TrapReason> {
+		if value == 0 {
+			return Err(Error::<E::T>::DecodingFailed.into())
+		}
+		self.ext().set_storage(value)?;
+		Ok(value)
+	}
+
+	/// Stores `value`.
+	#[api_version(0)]
+	fn store_unchecked(&mut self, _memory: &mut M, value: u32) -> Result<u32, TrapReason> {
+		self.ext().set_storage(value)?;
+		Ok(value)
+	}
+}
+
+fn call(ext: &mut MockExt, symbol: &[u8], value: u32) -> Result<Option<u64>, TrapReason> {
+	let mut memory = MockMemory { regs: [value, 0, 0, 0, 0, 0], ..Default::default() };
+	let mut runtime: Runtime<'_, _, MockMemory> = Runtime::new(ext, Vec::new());
+	runtime.handle_ecall(&mut memory, symbol, ApiVersion::UnsafeNewest)
+}
+
+#[test]
+fn storage_writes_fail_in_read_only_host_functions() {
+	let mut ext = MockExt::default();
+	assert!(matches!(call(&mut ext, b"store", 1), Err(TrapReason::SupervisorError(_))));
+	assert_eq!(ext.storage, None);
+	assert!(!ext.read_only);
+
+	assert!(matches!(call(&mut ext, b"store_unchecked", 1), Ok(Some(1))));
+	assert_eq!(ext.storage, Some(1));
+}
+
+#[test]
+fn read_only_setting_is_restored() {
+	// Also after the body returned early.
+	let mut ext = MockExt::default();
+	assert!(call(&mut ext, b"store", 0).is_err());
+	assert!(!ext.read_only);
+
+	// A read-only caller stays read-only.
+	let mut ext = MockExt { read_only: true, ..Default::default() };
+	assert!(call(&mut ext, b"store", 1).is_err());
+	assert!(ext.read_only);
+	assert!(call(&mut ext, b"store_unchecked", 1).is_err());
+	assert_eq!(ext.storage, None);
+}
@@ -0,0 +1,66 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Feeds out of range registers to a narrow integer syscall argument.
+//!
+//! The real environment has no such syscall, so this expands `#[define_env]` against a minimal
+//! stand-in for the pallet's runtime.
+
+#![cfg(not(feature = "verifier-only"))]
+#![allow(unexpected_cfgs)]
+
+extern crate alloc;
+
+mod common;
+
+use common::*;
+use pallet_revive_proc_macro::define_env;
+
+#[define_env]
+pub mod env {
+	/// Returns its narrow arguments widened to `u32`.
+	#[api_version(0)]
+	fn narrow(&mut self, _memory: &mut M, a: u8, b: i16) -> Result<u32, TrapReason> {
+		Ok(u32::from(a) << 16 | u32::from(b as u16))
+	}
+}
+
+fn call_narrow(a: u32, b: u32) -> Result<Option<u64>, TrapReason> {
+	let mut ext = MockExt::default();
+	let mut memory = MockMemory { regs: [a, b, 0, 0, 0, 0], ..Default::default() };
+	let mut runtime: Runtime<'_, _, MockMemory> = Runtime::new(&mut ext, Vec::new());
+	runtime.handle_ecall(&mut memory, b"narrow", ApiVersion::UnsafeNewest)
+}
+
+#[test]
+fn in_range_arguments_are_passed_through() {
+	assert!(matches!(call_narrow(255, (-2i32) as u32), Ok(Some(0xff_fffe))));
+}
+
+#[test]
+#[cfg(feature = "strict-args")]
+fn out_of_range_arguments_trap() {
+	assert!(matches!(call_narrow(256, 0), Err(TrapReason::SupervisorError(_))));
+	assert!(matches!(call_narrow(0, 1 << 15), Err(TrapReason::SupervisorError(_))));
+}
+
+#[test]
+#[cfg(not(feature = "strict-args"))]
+fn out_of_range_arguments_are_truncated() {
+	assert!(matches!(call_narrow(256 + 7, 0), Ok(Some(0x07_0000))));
+	assert!(matches!(call_narrow(0, 1 << 15), Ok(Some(0x8000))));
+}
@@ -0,0 +1,65 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checks the buckets returned by the generated `syscalls_by_tier()`.
+//!
+//! The real environment has no removed host functions, so this expands `#[define_env]` against a
+//! minimal stand-in for the pallet's runtime.
+
+#![cfg(not(feature = "verifier-only"))]
+#![allow(unexpected_cfgs)]
+
+extern crate alloc;
+
+mod common;
+
+use common::*;
+use pallet_revive_proc_macro::define_env;
+
+#[define_env]
+pub mod env {
+	/// Part of the stable interface.
+	#[api_version(0)]
+	fn stable(&mut self, _memory: &mut M) -> Result<(), TrapReason> {
+		Ok(())
+	}
+
+	/// Scheduled for removal.
+	#[api_version(0)]
+	#[deprecated]
+	fn deprecated(&mut self, _memory: &mut M) -> Result<(), TrapReason> {
+		Ok(())
+	}
+
+	fn unstable(&mut self, _memory: &mut M) -> Result<(), TrapReason> {
+		Ok(())
+	}
+
+	#[removed]
+	fn removed(&mut self, _memory: &mut M) -> Result<(), TrapReason> {
+		Ok(())
+	}
+}
+
+#[test]
+fn host_functions_land_in_their_tier() {
+	let tiers = syscalls_by_tier();
+	assert_eq!(tiers.stable, &[&b"stable"[..]]);
+	assert_eq!(tiers.deprecated, &[&b"deprecated"[..]]);
+	assert_eq!(tiers.unstable, &[&b"unstable"[..]]);
+	assert_eq!(tiers.removed, &[&b"removed"[..]]);
+}
@@ -20,6 +20,7 @@ pub use crate::{
 	primitives::ExecReturnValue,
 };
 use crate::{Config, LOG_TARGET};
+use core::{fmt::Debug, time::Duration};
 use sp_core::H160;
 
 /// Umbrella trait for all interfaces that serves for debugging.
@@ -48,6 +49,27 @@ pub trait Tracing<T: Config> {
 		entry_point: ExportedFunction,
 		input_data: &[u8],
 	) -> Self::CallSpan;
+
+	/// Called after a host function was executed.
+	///
+	/// Only invoked if `pallet-revive` is built with the `structured-strace` feature.
+	///
+	/// # Arguments
+	///
+	/// * `name` - The import symbol of the host function.
+	/// * `args` - The names and values of the arguments passed by the contract.
+	/// * `result` - The result of the host function.
+	fn trace_syscall(_name: &str, _args: &[(&str, &dyn Debug)], _result: &dyn Debug) {}
+
+	/// Called with the time a host function took to execute.
+	///
+	/// Only invoked if `pallet-revive` is built with the `host-timing` feature.
+	///
+	/// # Arguments
+	///
+	/// * `name` - The import symbol of the host function.
+	/// * `elapsed` - The wall clock time spent in the body of the host function.
+	fn record_host_time(_name: &str, _elapsed: Duration) {}
 }
 
 /// Defines a span of execution for a contract call.
@@ -66,6 +88,10 @@ impl<T: Config> Tracing<T> for () {
 	fn new_call_span(contract_address: &H160, entry_point: ExportedFunction, input_data: &[u8]) {
 		log::trace!(target: LOG_TARGET, "call {entry_point:?} address: {contract_address:?}, input_data: {input_data:?}")
 	}
+
+	fn trace_syscall(name: &str, args: &[(&str, &dyn Debug)], result: &dyn Debug) {
+		log::trace!(target: LOG_TARGET, "syscall {name} args: {args:?}, result: {result:?}")
+	}
 }
 
 impl CallSpan for () {
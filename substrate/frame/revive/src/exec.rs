@@ -28,7 +28,7 @@ use crate::{
 	Event, ImmutableData, ImmutableDataOf, Pallet as Contracts, LOG_TARGET,
 };
 use alloc::vec::Vec;
-use core::{fmt::Debug, marker::PhantomData, mem};
+use core::{fmt::Debug, marker::PhantomData, mem, time::Duration};
 use frame_support::{
 	crypto::ecdsa::ECDSAExt,
 	dispatch::{DispatchResult, DispatchResultWithPostInfo},
@@ -140,6 +140,15 @@ impl<T: Into<DispatchError>> From<T> for ExecError {
 	}
 }
 
+/// Returned by [`Ext::enter_read_only`] and needed to leave the read-only scope again.
+///
+/// It can't be constructed elsewhere, so a frame can only be made writable again if it was
+/// writable when the scope was entered.
+#[must_use]
+pub struct ReadOnlyScope {
+	was_read_only: bool,
+}
+
 /// The type of origins supported by the contracts pallet.
 #[derive(Clone, Encode, Decode, PartialEq, TypeInfo, RuntimeDebugNoBound)]
 pub enum Origin<T: Config> {
@@ -391,6 +400,18 @@ pub trait Ext: sealing::Sealed {
 	/// Returns `true` if debug message recording is enabled. Otherwise `false` is returned.
 	fn debug_buffer_enabled(&self) -> bool;
 
+	/// Records a call to the deprecated syscall `name`.
+	///
+	/// Returns `true` only for the first call to it within this call stack. Used to emit each
+	/// deprecation note once.
+	fn note_deprecated_syscall(&mut self, name: &'static str) -> bool;
+
+	/// Pass a structured record of a host function call to [`Config::Debug`].
+	fn trace_syscall(&self, name: &str, args: &[(&str, &dyn Debug)], result: &dyn Debug);
+
+	/// Pass the time a host function took to execute to [`Config::Debug`].
+	fn record_host_time(&self, name: &str, elapsed: Duration);
+
 	/// Call some dispatchable and return the result.
 	fn call_runtime(&self, call: <Self::T as Config>::RuntimeCall) -> DispatchResultWithPostInfo;
 
@@ -464,6 +485,15 @@ pub trait Ext: sealing::Sealed {
 	/// Check if running in read-only context.
 	fn is_read_only(&self) -> bool;
 
+	/// Marks the current frame read-only until the returned scope is passed to
+	/// [`Self::exit_read_only`].
+	///
+	/// Used to run `#[read_only]` host functions. Storage writes fail while it is set.
+	fn enter_read_only(&mut self) -> ReadOnlyScope;
+
+	/// Restores the read-only setting the current frame had before `scope` was entered.
+	fn exit_read_only(&mut self, scope: ReadOnlyScope);
+
 	/// Returns an immutable reference to the output of the last executed call frame.
 	fn last_frame_output(&self) -> &ExecReturnValue;
 
@@ -560,6 +590,8 @@ pub struct Stack<'a, T: Config, E> {
 	/// All the bytes added to this field should be valid UTF-8. The buffer has no defined
 	/// structure and is intended to be shown to users as-is for debugging purposes.
 	debug_message: Option<&'a mut DebugBuffer>,
+	/// The deprecated syscalls which were called within this call stack.
+	deprecated_syscalls: Vec<&'static str>,
 	/// Transient storage used to store data, which is kept for the duration of a transaction.
 	transient_storage: TransientStorage<T>,
 	/// No executable is held by the struct but influences its behaviour.
@@ -895,6 +927,7 @@ where
 			first_frame,
 			frames: Default::default(),
 			debug_message,
+			deprecated_syscalls: Vec::new(),
 			transient_storage: TransientStorage::new(limits::TRANSIENT_STORAGE_BYTES),
 			_phantom: Default::default(),
 		};
@@ -1589,6 +1622,9 @@ where
 		value: Option<Vec<u8>>,
 		take_old: bool,
 	) -> Result<WriteOutcome, DispatchError> {
+		if self.is_read_only() {
+			return Err(Error::<T>::StateChangeDenied.into());
+		}
 		let frame = self.top_frame_mut();
 		frame.contract_info.get(&frame.account_id).write(
 			key.into(),
@@ -1614,6 +1650,9 @@ where
 		value: Option<Vec<u8>>,
 		take_old: bool,
 	) -> Result<WriteOutcome, DispatchError> {
+		if self.is_read_only() {
+			return Err(Error::<T>::StateChangeDenied.into());
+		}
 		let account_id = self.account_id().clone();
 		self.transient_storage.write(&account_id, key, value, take_old)
 	}
@@ -1762,6 +1801,22 @@ where
 		self.debug_message.is_some()
 	}
 
+	fn note_deprecated_syscall(&mut self, name: &'static str) -> bool {
+		if self.deprecated_syscalls.contains(&name) {
+			return false
+		}
+		self.deprecated_syscalls.push(name);
+		true
+	}
+
+	fn trace_syscall(&self, name: &str, args: &[(&str, &dyn Debug)], result: &dyn Debug) {
+		T::Debug::trace_syscall(name, args, result)
+	}
+
+	fn record_host_time(&self, name: &str, elapsed: Duration) {
+		T::Debug::record_host_time(name, elapsed)
+	}
+
 	fn append_debug_buffer(&mut self, msg: &str) -> bool {
 		if let Some(buffer) = &mut self.debug_message {
 			buffer
@@ -1909,6 +1964,15 @@ where
 		self.top_frame().read_only
 	}
 
+	fn enter_read_only(&mut self) -> ReadOnlyScope {
+		let was_read_only = core::mem::replace(&mut self.top_frame_mut().read_only, true);
+		ReadOnlyScope { was_read_only }
+	}
+
+	fn exit_read_only(&mut self, scope: ReadOnlyScope) {
+		self.top_frame_mut().read_only = scope.was_read_only;
+	}
+
 	fn last_frame_output(&self) -> &ExecReturnValue {
 		&self.top_frame().last_frame_output
 	}
@@ -3941,6 +4005,123 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn deprecated_syscalls_are_noted_once_per_call_stack() {
+		let code_hash = MockLoader::insert(Call, |ctx, _| {
+			assert!(ctx.ext.note_deprecated_syscall("seal_foo"));
+			assert!(!ctx.ext.note_deprecated_syscall("seal_foo"));
+			assert!(ctx.ext.note_deprecated_syscall("seal_bar"));
+			exec_success()
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let min_balance = <Test as Config>::Currency::minimum_balance();
+			set_balance(&ALICE, min_balance * 1000);
+			place_contract(&BOB, code_hash);
+			let origin = Origin::from_account_id(ALICE);
+
+			// Each call stack notes the syscall anew.
+			for _ in 0..2 {
+				let mut gas_meter = GasMeter::<Test>::new(GAS_LIMIT);
+				let mut storage_meter =
+					storage::meter::Meter::new(&origin, deposit_limit::<Test>(), 0).unwrap();
+				assert_ok!(MockStack::run_call(
+					origin.clone(),
+					BOB_ADDR,
+					&mut gas_meter,
+					&mut storage_meter,
+					U256::zero(),
+					vec![],
+					None,
+				));
+			}
+		});
+	}
+
+	#[test]
+	fn read_only_scope_denies_storage_writes() {
+		let code_hash = MockLoader::insert(Call, |ctx, _| {
+			let key = Key::Fix([1; 32]);
+			let outer = ctx.ext.enter_read_only();
+			let inner = ctx.ext.enter_read_only();
+			assert!(ctx.ext.is_read_only());
+			assert_err!(
+				ctx.ext.set_storage(&key, Some(vec![1]), false),
+				<Error<Test>>::StateChangeDenied
+			);
+			assert_err!(
+				ctx.ext.set_transient_storage(&key, Some(vec![1]), false),
+				<Error<Test>>::StateChangeDenied
+			);
+
+			// Leaving a nested scope keeps the frame read-only.
+			ctx.ext.exit_read_only(inner);
+			assert!(ctx.ext.is_read_only());
+			ctx.ext.exit_read_only(outer);
+			assert!(!ctx.ext.is_read_only());
+			assert_eq!(ctx.ext.set_storage(&key, Some(vec![1]), false), Ok(WriteOutcome::New));
+			exec_success()
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let min_balance = <Test as Config>::Currency::minimum_balance();
+
+			let mut gas_meter = GasMeter::<Test>::new(GAS_LIMIT);
+			set_balance(&ALICE, min_balance * 1000);
+			place_contract(&BOB, code_hash);
+			let origin = Origin::from_account_id(ALICE);
+			let mut storage_meter =
+				storage::meter::Meter::new(&origin, deposit_limit::<Test>(), 0).unwrap();
+			assert_ok!(MockStack::run_call(
+				origin,
+				BOB_ADDR,
+				&mut gas_meter,
+				&mut storage_meter,
+				U256::zero(),
+				vec![],
+				None,
+			));
+		});
+	}
+
+	#[test]
+	fn host_functions_can_be_called_directly() {
+		use crate::wasm::{mock_caller, MockMemory};
+
+		let code_hash = MockLoader::insert(Call, |ctx, _| {
+			let gas_before = ctx.ext.gas_meter().gas_left();
+			let mut memory = MockMemory::new(&*ctx.ext, [4, 0, 0, 0, 0, 0], vec![0; 24]);
+			assert_matches!(mock_caller(ctx.ext, &mut memory), Ok(None));
+			assert_eq!(&memory.memory[4..], ALICE_ADDR.as_bytes());
+			assert!(ctx.ext.gas_meter().gas_left().ref_time() < gas_before.ref_time());
+
+			// The output doesn't fit into memory anymore.
+			memory.regs[0] = 5;
+			assert_matches!(mock_caller(ctx.ext, &mut memory), Err(_));
+			exec_success()
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let min_balance = <Test as Config>::Currency::minimum_balance();
+
+			let mut gas_meter = GasMeter::<Test>::new(GAS_LIMIT);
+			set_balance(&ALICE, min_balance * 1000);
+			place_contract(&BOB, code_hash);
+			let origin = Origin::from_account_id(ALICE);
+			let mut storage_meter =
+				storage::meter::Meter::new(&origin, deposit_limit::<Test>(), 0).unwrap();
+			assert_ok!(MockStack::run_call(
+				origin,
+				BOB_ADDR,
+				&mut gas_meter,
+				&mut storage_meter,
+				U256::zero(),
+				vec![],
+				None,
+			));
+		});
+	}
+
 	#[test]
 	fn set_storage_varsized_key_works() {
 		let code_hash = MockLoader::insert(Call, |ctx, _| {
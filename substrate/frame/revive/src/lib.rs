@@ -85,6 +85,14 @@ pub use crate::{
 	pallet::*,
 };
 pub use primitives::*;
+pub use wasm::{
+	compatibility_report, context_safe_syscalls, expected_signature, intern, is_compatible_with,
+	is_stable_syscall, list_syscalls, list_syscalls_with_versions, resolve_symbol,
+	resolve_syscall_id, syscall_arity, syscall_max_output, syscalls_by_cost, syscalls_by_tier,
+	syscalls_writing_memory, unstable_hostfn_syscalls, validate_call, CallError,
+	CompatibilityReport, Syscall, SyscallId, SyscallsByTier, STABLE_SYSCALL_COUNT,
+	SYSCALL_ABI_HASH, SYSCALL_COUNT, SYSCALL_MANIFEST, SYSCALL_NAMES, SYSCALL_TABLE_MD,
+};
 pub use weights::WeightInfo;
 
 #[cfg(doc)]
@@ -128,6 +136,192 @@ fn api_version_up_to_date() {
 	);
 }
 
+#[test]
+fn syscall_names_not_too_long() {
+	assert!(
+		crate::wasm::OVERLONG_SYSCALLS.is_empty(),
+		"The following syscall names are too long: {:?}",
+		crate::wasm::OVERLONG_SYSCALLS,
+	);
+}
+
+#[test]
+fn validate_call_rejects_dirty_registers() {
+	use crate::wasm::{validate_call, CallError};
+
+	// `caller` only takes a single argument.
+	assert_eq!(validate_call(b"caller", &[1, 0, 0, 0, 0, 0]), Ok(()));
+	assert_eq!(validate_call(b"caller", &[1, 0, 1, 0, 0, 0]), Err(CallError::DirtyRegister(2)));
+	assert_eq!(validate_call(b"unknown", &[0; 6]), Err(CallError::UnknownSyscall));
+}
+
+#[test]
+fn resolve_symbol_works() {
+	assert_eq!(crate::wasm::resolve_symbol("caller"), Some(&b"caller"[..]));
+	assert_eq!(crate::wasm::resolve_symbol("unknown"), None);
+}
+
+#[test]
+fn resolve_syscall_id_works() {
+	assert!(crate::wasm::resolve_syscall_id(b"caller").is_some());
+	assert_eq!(crate::wasm::resolve_syscall_id(b"unknown"), None);
+}
+
+#[test]
+fn syscall_enum_works() {
+	use crate::wasm::Syscall;
+	assert_eq!(Syscall::from_symbol(b"caller"), Some(Syscall::Caller));
+	assert_eq!(Syscall::Caller.as_symbol(), b"caller");
+	assert_eq!(Syscall::from_symbol(b"unknown"), None);
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+#[test]
+fn bench_syscalls_work() {
+	use crate::wasm::{symbol_to_index, BENCH_SYSCALLS};
+	let idx = symbol_to_index("caller").unwrap();
+	assert_eq!(BENCH_SYSCALLS[idx], "caller");
+	assert_eq!(symbol_to_index("unknown"), None);
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+#[test]
+fn required_costs_are_benchmarked() {
+	use crate::{tests::Test, wasm::REQUIRED_COSTS};
+	use frame_benchmarking::Benchmarking;
+
+	// Those are only charged for the bytes they copy or the weight of what they dispatch.
+	let priced_otherwise = [
+		"chain_id",
+		"return_data_size",
+		"return_data_copy",
+		"call_chain_extension",
+		"call_runtime",
+		"xcm_execute",
+		"xcm_send",
+	];
+	let benchmarks = <Pallet<Test> as Benchmarking>::benchmarks(false)
+		.into_iter()
+		.map(|benchmark| String::from_utf8(benchmark.name).unwrap())
+		.collect::<Vec<_>>();
+	for name in REQUIRED_COSTS {
+		if priced_otherwise.contains(name) {
+			continue
+		}
+		let candidates = match *name {
+			"noop" => vec!["noop_host_fn".to_string()],
+			name => vec![name.to_string(), format!("seal_{name}")],
+		};
+		assert!(
+			candidates.iter().any(|candidate| benchmarks.contains(candidate)),
+			"no benchmark for syscall {name}"
+		);
+	}
+	for name in priced_otherwise {
+		assert!(REQUIRED_COSTS.contains(&name), "{name} is not a syscall anymore");
+	}
+}
+
+#[test]
+fn intern_works() {
+	let id = crate::wasm::intern("caller").unwrap();
+	assert_eq!(id.name(), "caller");
+	assert_eq!(crate::wasm::intern("unknown"), None);
+}
+
+#[test]
+fn syscall_max_output_works() {
+	assert_eq!(crate::wasm::syscall_max_output(b"caller"), Some(20));
+	assert_eq!(crate::wasm::syscall_max_output(b"input"), None);
+}
+
+#[test]
+fn syscalls_by_cost_works() {
+	use crate::{tests::Test, wasm::RuntimeCosts};
+
+	let costs = crate::wasm::syscalls_by_cost::<Test>();
+	let position = |name| costs.iter().position(|(n, _)| *n == name).unwrap();
+	assert_eq!(costs[position("caller")].1, RuntimeCosts::Caller);
+	assert!(position("ecdsa_recover") < position("caller"));
+}
+
+#[test]
+fn compatibility_report_works() {
+	use crate::wasm::{
+		compatibility_report, expected_signature, is_compatible_with, SYSCALL_ABI_HASH,
+	};
+
+	assert!(is_compatible_with(SYSCALL_ABI_HASH));
+	assert!(!is_compatible_with([0; 32]));
+	let report = compatibility_report(&[
+		(b"caller", "fn(u32)"),
+		(b"seal_gone", "fn()"),
+		(b"is_contract", "fn(u64) -> u32"),
+		(b"call_runtime", expected_signature(b"call_runtime").unwrap()),
+	]);
+	assert_eq!(report.missing, vec![&b"seal_gone"[..]]);
+	assert_eq!(report.changed, vec![&b"is_contract"[..]]);
+	assert_eq!(report.unstable, vec![&b"call_runtime"[..]]);
+	assert!(!report.is_compatible());
+
+	let report = compatibility_report(&[(b"caller", "fn(u32)")]);
+	assert_eq!(report, Default::default());
+	assert!(report.is_compatible());
+}
+
+#[test]
+fn syscall_counts_up_to_date() {
+	use crate::wasm::{list_syscalls, STABLE_SYSCALL_COUNT, SYSCALL_COUNT};
+
+	assert_eq!(SYSCALL_COUNT, list_syscalls(true).len());
+	assert_eq!(STABLE_SYSCALL_COUNT, list_syscalls(false).len());
+	// Adding or removing a stable syscall changes the interface every contract relies on.
+	assert_eq!(STABLE_SYSCALL_COUNT, 45);
+}
+
+#[test]
+fn syscall_versions_match_symbols() {
+	use crate::wasm::{list_syscalls, list_syscalls_with_versions};
+
+	for include_unstable in [false, true] {
+		let versioned = list_syscalls_with_versions(include_unstable);
+		let symbols = versioned.iter().map(|(symbol, _)| *symbol).collect::<Vec<_>>();
+		assert_eq!(symbols, list_syscalls(include_unstable));
+	}
+}
+
+#[test]
+fn is_stable_syscall_works() {
+	use crate::wasm::{is_stable_syscall, list_syscalls};
+
+	for symbol in list_syscalls(true) {
+		assert_eq!(is_stable_syscall(symbol), list_syscalls(false).contains(symbol));
+	}
+	assert!(is_stable_syscall(b"caller"));
+	assert!(!is_stable_syscall(b"unknown"));
+}
+
+#[test]
+fn syscall_abi_hash_up_to_date() {
+	// Changing the signature of a stable syscall breaks every contract relying on it.
+	assert_eq!(
+		crate::wasm::SYSCALL_ABI_HASH,
+		hex_literal::hex!("6fd1be2f15e7af2b68f5464491520625708937a4402fedc5c7de9c42f64d2a89"),
+	);
+}
+
+#[test]
+fn syscall_manifest_works() {
+	let caller = r#"{"name":"caller","stable":true,"arity":1,"args":["u32"],"returns":"()"}"#;
+	assert!(crate::wasm::SYSCALL_MANIFEST.contains(caller));
+}
+
+#[test]
+fn syscall_table_works() {
+	let caller = "| `caller` | `out_ptr: u32` | `()` | yes |\n";
+	assert!(crate::wasm::SYSCALL_TABLE_MD.contains(caller));
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
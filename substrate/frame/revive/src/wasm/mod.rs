@@ -24,12 +24,22 @@ mod runtime;
 pub use crate::wasm::runtime::SyscallDoc;
 
 #[cfg(test)]
-pub use runtime::HIGHEST_API_VERSION;
+pub use runtime::{mock_caller, MockMemory, HIGHEST_API_VERSION, OVERLONG_SYSCALLS};
 
 #[cfg(feature = "runtime-benchmarks")]
-pub use crate::wasm::runtime::{ReturnData, TrapReason};
+pub use crate::wasm::runtime::{
+	symbol_to_index, ReturnData, TrapReason, BENCH_SYSCALLS, REQUIRED_COSTS,
+};
 
-pub use crate::wasm::runtime::{ApiVersion, Memory, Runtime, RuntimeCosts};
+pub use crate::wasm::runtime::{
+	compatibility_report, context_safe_syscalls, expected_signature, intern, is_compatible_with,
+	is_stable_syscall, list_syscalls, list_syscalls_with_versions, resolve_symbol,
+	resolve_syscall_id, syscall_arity, syscall_max_output, syscalls_by_cost, syscalls_by_tier,
+	syscalls_writing_memory, unstable_hostfn_syscalls, validate_call, ApiVersion, CallError,
+	CompatibilityReport, Memory, Runtime, RuntimeCosts, Syscall, SyscallId, SyscallsByTier,
+	STABLE_SYSCALL_COUNT, SYSCALL_ABI_HASH, SYSCALL_COUNT, SYSCALL_MANIFEST, SYSCALL_NAMES,
+	SYSCALL_TABLE_MD,
+};
 
 use crate::{
 	address::AddressMapper,
@@ -257,6 +267,8 @@ impl<T: Config> CodeInfo<T> {
 
 pub struct PreparedCall<'a, E: Ext> {
 	module: polkavm::Module,
+	/// The [`SyscallId`] of each import of `module`, if it is known.
+	syscall_ids: Vec<Option<SyscallId>>,
 	instance: polkavm::RawInstance,
 	runtime: Runtime<'a, E, polkavm::RawInstance>,
 	api_version: ApiVersion,
@@ -273,6 +285,7 @@ where
 			if let Some(exec_result) = self.runtime.handle_interrupt(
 				interrupt,
 				&self.module,
+				&self.syscall_ids,
 				&mut self.instance,
 				self.api_version,
 			) {
@@ -306,6 +319,13 @@ impl<T: Config> WasmBlob<T> {
 			Error::<T>::CodeRejected
 		})?;
 
+		// Resolved once so that no symbol needs to be compared when a syscall is called.
+		let syscall_ids = module
+			.imports()
+			.iter()
+			.map(|symbol| symbol.and_then(|symbol| resolve_syscall_id(symbol.as_bytes())))
+			.collect();
+
 		let entry_program_counter = module
 			.exports()
 			.find(|export| export.symbol().as_bytes() == entry_point.identifier().as_bytes())
@@ -327,7 +347,7 @@ impl<T: Config> WasmBlob<T> {
 		instance.set_gas(gas_limit_polkavm);
 		instance.prepare_call_untyped(entry_program_counter, &[]);
 
-		Ok(PreparedCall { module, instance, runtime, api_version })
+		Ok(PreparedCall { module, syscall_ids, instance, runtime, api_version })
 	}
 }
 
@@ -165,7 +165,8 @@ pub trait PolkaVmInstance<T: Config>: Memory<T> {
 	fn gas(&self) -> polkavm::Gas;
 	fn set_gas(&mut self, gas: polkavm::Gas);
 	fn read_input_regs(&self) -> (u32, u32, u32, u32, u32, u32);
-	fn write_output(&mut self, output: u32);
+	/// Writes the low half of `output` into `a0` and the high half into `a1`.
+	fn write_output(&mut self, output: u64);
 }
 
 // Memory implementation used in benchmarking where guest memory is mapped into the host.
@@ -225,8 +226,73 @@ impl<T: Config> PolkaVmInstance<T> for polkavm::RawInstance {
 		)
 	}
 
-	fn write_output(&mut self, output: u32) {
-		self.set_reg(polkavm::Reg::A0, output);
+	fn write_output(&mut self, output: u64) {
+		self.set_reg(polkavm::Reg::A0, output as u32);
+		self.set_reg(polkavm::Reg::A1, (output >> 32) as u32);
+	}
+}
+
+/// Registers and memory of a guest which are passed to the generated `mock_<symbol>` functions.
+///
+/// Allows unit tests to call a host function without compiling and executing a contract.
+#[cfg(test)]
+pub struct MockMemory {
+	/// The argument registers `a0` to `a5`.
+	pub regs: [u32; 6],
+	/// The guest memory starting at address zero.
+	pub memory: Vec<u8>,
+	/// The value written to `a0` and `a1` by the last host function, if any.
+	pub output: Option<u64>,
+	gas: polkavm::Gas,
+}
+
+#[cfg(test)]
+impl MockMemory {
+	/// Creates a guest which has all the gas left that `ext` has.
+	pub fn new<E: Ext>(ext: &E, regs: [u32; 6], memory: Vec<u8>) -> Self {
+		let gas = ext.gas_meter().engine_fuel_left().expect("gas limit of tests fits");
+		Self { regs, memory, output: None, gas }
+	}
+}
+
+#[cfg(test)]
+impl<T: Config> Memory<T> for MockMemory {
+	fn read_into_buf(&self, ptr: u32, buf: &mut [u8]) -> Result<(), DispatchError> {
+		let ptr = ptr as usize;
+		let bound_checked =
+			self.memory.get(ptr..ptr + buf.len()).ok_or_else(|| Error::<T>::OutOfBounds)?;
+		buf.copy_from_slice(bound_checked);
+		Ok(())
+	}
+
+	fn write(&mut self, ptr: u32, buf: &[u8]) -> Result<(), DispatchError> {
+		let ptr = ptr as usize;
+		let bound_checked = self
+			.memory
+			.get_mut(ptr..ptr + buf.len())
+			.ok_or_else(|| Error::<T>::OutOfBounds)?;
+		bound_checked.copy_from_slice(buf);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+impl<T: Config> PolkaVmInstance<T> for MockMemory {
+	fn gas(&self) -> polkavm::Gas {
+		self.gas
+	}
+
+	fn set_gas(&mut self, gas: polkavm::Gas) {
+		self.gas = gas
+	}
+
+	fn read_input_regs(&self) -> (u32, u32, u32, u32, u32, u32) {
+		let [a0, a1, a2, a3, a4, a5] = self.regs;
+		(a0, a1, a2, a3, a4, a5)
+	}
+
+	fn write_output(&mut self, output: u64) {
+		self.output = Some(output);
 	}
 }
 
@@ -571,6 +637,7 @@ impl<'a, E: Ext, M: PolkaVmInstance<E::T>> Runtime<'a, E, M> {
 		&mut self,
 		interrupt: Result<polkavm::InterruptKind, polkavm::Error>,
 		module: &polkavm::Module,
+		syscall_ids: &[Option<SyscallId>],
 		instance: &mut M,
 		api_version: ApiVersion,
 	) -> Option<ExecResult> {
@@ -589,10 +656,16 @@ impl<'a, E: Ext, M: PolkaVmInstance<E::T>> Runtime<'a, E, M> {
 			Ok(NotEnoughGas) => Some(Err(Error::<E::T>::OutOfGas.into())),
 			Ok(Step) => None,
 			Ok(Ecalli(idx)) => {
-				let Some(syscall_symbol) = module.imports().get(idx) else {
-					return Some(Err(<Error<E::T>>::InvalidSyscall.into()));
+				let result = if let Some(&Some(syscall_id)) = syscall_ids.get(idx as usize) {
+					self.handle_ecall_by_id(instance, syscall_id, api_version)
+				} else {
+					// Unknown imports are passed by their symbol so that it shows up in the trace.
+					let Some(syscall_symbol) = module.imports().get(idx) else {
+						return Some(Err(<Error<E::T>>::InvalidSyscall.into()));
+					};
+					self.handle_ecall(instance, syscall_symbol.as_bytes(), api_version)
 				};
-				match self.handle_ecall(instance, syscall_symbol.as_bytes(), api_version) {
+				match result {
 					Ok(None) => None,
 					Ok(Some(return_value)) => {
 						instance.write_output(return_value);
@@ -1191,6 +1264,7 @@ pub mod env {
 	/// Retrieve the value under the given key from storage.
 	/// See [`pallet_revive_uapi::HostFn::get_storage`]
 	#[api_version(0)]
+	#[writes_memory]
 	fn get_storage(
 		&mut self,
 		memory: &mut M,
@@ -1220,6 +1294,7 @@ pub mod env {
 	/// See [`pallet_revive_uapi::HostFn::take_storage`]
 	#[api_version(0)]
 	#[mutating]
+	#[writes_memory]
 	fn take_storage(
 		&mut self,
 		memory: &mut M,
@@ -1235,6 +1310,7 @@ pub mod env {
 	/// Make a call to another contract.
 	/// See [`pallet_revive_uapi::HostFn::call`].
 	#[api_version(0)]
+	#[writes_memory]
 	fn call(
 		&mut self,
 		memory: &mut M,
@@ -1266,6 +1342,7 @@ pub mod env {
 	/// Execute code in the context (storage, caller, value) of the current contract.
 	/// See [`pallet_revive_uapi::HostFn::delegate_call`].
 	#[api_version(0)]
+	#[writes_memory]
 	fn delegate_call(
 		&mut self,
 		memory: &mut M,
@@ -1297,6 +1374,7 @@ pub mod env {
 	/// See [`pallet_revive_uapi::HostFn::instantiate`].
 	#[api_version(0)]
 	#[mutating]
+	#[writes_memory]
 	fn instantiate(
 		&mut self,
 		memory: &mut M,
@@ -1338,6 +1416,7 @@ pub mod env {
 	/// Stores the input passed by the caller into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::input`].
 	#[api_version(0)]
+	#[writes_memory]
 	fn input(&mut self, memory: &mut M, out_ptr: u32, out_len_ptr: u32) -> Result<(), TrapReason> {
 		if let Some(input) = self.input_data.take() {
 			self.write_sandbox_output(memory, out_ptr, out_len_ptr, &input, false, |len| {
@@ -1367,8 +1446,10 @@ pub mod env {
 	/// Stores the address of the caller into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::caller`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[max_output(20)]
+	#[gas(RuntimeCosts::Caller)]
 	fn caller(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::Caller)?;
 		let caller = <E::T as Config>::AddressMapper::to_address(self.ext.caller().account_id()?);
 		Ok(self.write_fixed_sandbox_output(
 			memory,
@@ -1382,8 +1463,10 @@ pub mod env {
 	/// Stores the address of the call stack origin into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::origin`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[max_output(20)]
+	#[gas(RuntimeCosts::Origin)]
 	fn origin(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::Origin)?;
 		let origin = <E::T as Config>::AddressMapper::to_address(self.ext.origin().account_id()?);
 		Ok(self.write_fixed_sandbox_output(
 			memory,
@@ -1397,8 +1480,8 @@ pub mod env {
 	/// Checks whether a specified address belongs to a contract.
 	/// See [`pallet_revive_uapi::HostFn::is_contract`].
 	#[api_version(0)]
+	#[gas(RuntimeCosts::IsContract)]
 	fn is_contract(&mut self, memory: &mut M, account_ptr: u32) -> Result<u32, TrapReason> {
-		self.charge_gas(RuntimeCosts::IsContract)?;
 		let address = memory.read_h160(account_ptr)?;
 		Ok(self.ext.is_contract(&address) as u32)
 	}
@@ -1406,8 +1489,10 @@ pub mod env {
 	/// Retrieve the code hash for a specified contract address.
 	/// See [`pallet_revive_uapi::HostFn::code_hash`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[max_output(32)]
+	#[gas(RuntimeCosts::CodeHash)]
 	fn code_hash(&mut self, memory: &mut M, addr_ptr: u32, out_ptr: u32) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::CodeHash)?;
 		let address = memory.read_h160(addr_ptr)?;
 		Ok(self.write_fixed_sandbox_output(
 			memory,
@@ -1421,8 +1506,9 @@ pub mod env {
 	/// Retrieve the code size for a given contract address.
 	/// See [`pallet_revive_uapi::HostFn::code_size`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[gas(RuntimeCosts::CodeSize)]
 	fn code_size(&mut self, memory: &mut M, addr_ptr: u32, out_ptr: u32) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::CodeSize)?;
 		let address = memory.read_h160(addr_ptr)?;
 		Ok(self.write_fixed_sandbox_output(
 			memory,
@@ -1436,8 +1522,10 @@ pub mod env {
 	/// Retrieve the code hash of the currently executing contract.
 	/// See [`pallet_revive_uapi::HostFn::own_code_hash`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[max_output(32)]
+	#[gas(RuntimeCosts::OwnCodeHash)]
 	fn own_code_hash(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::OwnCodeHash)?;
 		let code_hash = *self.ext.own_code_hash();
 		Ok(self.write_fixed_sandbox_output(
 			memory,
@@ -1451,24 +1539,26 @@ pub mod env {
 	/// Checks whether the caller of the current contract is the origin of the whole call stack.
 	/// See [`pallet_revive_uapi::HostFn::caller_is_origin`].
 	#[api_version(0)]
+	#[gas(RuntimeCosts::CallerIsOrigin)]
 	fn caller_is_origin(&mut self, _memory: &mut M) -> Result<u32, TrapReason> {
-		self.charge_gas(RuntimeCosts::CallerIsOrigin)?;
 		Ok(self.ext.caller_is_origin() as u32)
 	}
 
 	/// Checks whether the caller of the current contract is root.
 	/// See [`pallet_revive_uapi::HostFn::caller_is_root`].
 	#[api_version(0)]
+	#[gas(RuntimeCosts::CallerIsRoot)]
 	fn caller_is_root(&mut self, _memory: &mut M) -> Result<u32, TrapReason> {
-		self.charge_gas(RuntimeCosts::CallerIsRoot)?;
 		Ok(self.ext.caller_is_root() as u32)
 	}
 
 	/// Stores the address of the current contract into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::address`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[max_output(20)]
+	#[gas(RuntimeCosts::Address)]
 	fn address(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::Address)?;
 		let address = self.ext.address();
 		Ok(self.write_fixed_sandbox_output(
 			memory,
@@ -1482,6 +1572,8 @@ pub mod env {
 	/// Stores the price for the specified amount of weight into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::weight_to_fee`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[gas(RuntimeCosts::WeightToFee)]
 	fn weight_to_fee(
 		&mut self,
 		memory: &mut M,
@@ -1490,7 +1582,6 @@ pub mod env {
 		out_ptr: u32,
 	) -> Result<(), TrapReason> {
 		let weight = Weight::from_parts(ref_time_limit, proof_size_limit);
-		self.charge_gas(RuntimeCosts::WeightToFee)?;
 		Ok(self.write_fixed_sandbox_output(
 			memory,
 			out_ptr,
@@ -1503,13 +1594,14 @@ pub mod env {
 	/// Stores the amount of weight left into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::weight_left`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[gas(RuntimeCosts::WeightLeft)]
 	fn weight_left(
 		&mut self,
 		memory: &mut M,
 		out_ptr: u32,
 		out_len_ptr: u32,
 	) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::WeightLeft)?;
 		let gas_left = &self.ext.gas_meter().gas_left().encode();
 		Ok(self.write_sandbox_output(
 			memory,
@@ -1524,6 +1616,7 @@ pub mod env {
 	/// Stores the immutable data into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::get_immutable_data`].
 	#[api_version(0)]
+	#[writes_memory]
 	fn get_immutable_data(
 		&mut self,
 		memory: &mut M,
@@ -1554,8 +1647,10 @@ pub mod env {
 	/// Stores the *free* balance of the current account into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::balance`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[max_output(32)]
+	#[gas(RuntimeCosts::Balance)]
 	fn balance(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::Balance)?;
 		Ok(self.write_fixed_sandbox_output(
 			memory,
 			out_ptr,
@@ -1568,13 +1663,14 @@ pub mod env {
 	/// Stores the *free* balance of the supplied address into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::balance`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[gas(RuntimeCosts::BalanceOf)]
 	fn balance_of(
 		&mut self,
 		memory: &mut M,
 		addr_ptr: u32,
 		out_ptr: u32,
 	) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::BalanceOf)?;
 		let address = memory.read_h160(addr_ptr)?;
 		Ok(self.write_fixed_sandbox_output(
 			memory,
@@ -1588,6 +1684,8 @@ pub mod env {
 	/// Returns the chain ID.
 	/// See [`pallet_revive_uapi::HostFn::chain_id`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[max_output(32)]
 	fn chain_id(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
 		Ok(self.write_fixed_sandbox_output(
 			memory,
@@ -1601,8 +1699,9 @@ pub mod env {
 	/// Stores the value transferred along with this call/instantiate into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::value_transferred`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[gas(RuntimeCosts::ValueTransferred)]
 	fn value_transferred(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::ValueTransferred)?;
 		Ok(self.write_fixed_sandbox_output(
 			memory,
 			out_ptr,
@@ -1615,8 +1714,9 @@ pub mod env {
 	/// Load the latest block timestamp into the supplied buffer
 	/// See [`pallet_revive_uapi::HostFn::now`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[gas(RuntimeCosts::Now)]
 	fn now(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::Now)?;
 		Ok(self.write_fixed_sandbox_output(
 			memory,
 			out_ptr,
@@ -1629,8 +1729,9 @@ pub mod env {
 	/// Stores the minimum balance (a.k.a. existential deposit) into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::minimum_balance`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[gas(RuntimeCosts::MinimumBalance)]
 	fn minimum_balance(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::MinimumBalance)?;
 		Ok(self.write_fixed_sandbox_output(
 			memory,
 			out_ptr,
@@ -1683,8 +1784,9 @@ pub mod env {
 	/// Stores the current block number of the current contract into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::block_number`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[gas(RuntimeCosts::BlockNumber)]
 	fn block_number(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::BlockNumber)?;
 		Ok(self.write_fixed_sandbox_output(
 			memory,
 			out_ptr,
@@ -1697,13 +1799,14 @@ pub mod env {
 	/// Stores the block hash at given block height into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::block_hash`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[gas(RuntimeCosts::BlockHash)]
 	fn block_hash(
 		&mut self,
 		memory: &mut M,
 		block_number_ptr: u32,
 		out_ptr: u32,
 	) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::BlockHash)?;
 		let block_number = memory.read_u256(block_number_ptr)?;
 		let block_hash = self.ext.block_hash(block_number).unwrap_or(H256::zero());
 		Ok(self.write_fixed_sandbox_output(
@@ -1718,6 +1821,9 @@ pub mod env {
 	/// Computes the SHA2 256-bit hash on the given input buffer.
 	/// See [`pallet_revive_uapi::HostFn::hash_sha2_256`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[max_output(32)]
+	#[context_safe]
 	fn hash_sha2_256(
 		&mut self,
 		memory: &mut M,
@@ -1734,6 +1840,9 @@ pub mod env {
 	/// Computes the KECCAK 256-bit hash on the given input buffer.
 	/// See [`pallet_revive_uapi::HostFn::hash_keccak_256`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[max_output(32)]
+	#[context_safe]
 	fn hash_keccak_256(
 		&mut self,
 		memory: &mut M,
@@ -1750,6 +1859,9 @@ pub mod env {
 	/// Computes the BLAKE2 256-bit hash on the given input buffer.
 	/// See [`pallet_revive_uapi::HostFn::hash_blake2_256`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[max_output(32)]
+	#[context_safe]
 	fn hash_blake2_256(
 		&mut self,
 		memory: &mut M,
@@ -1766,6 +1878,9 @@ pub mod env {
 	/// Computes the BLAKE2 128-bit hash on the given input buffer.
 	/// See [`pallet_revive_uapi::HostFn::hash_blake2_128`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[max_output(16)]
+	#[context_safe]
 	fn hash_blake2_128(
 		&mut self,
 		memory: &mut M,
@@ -1781,6 +1896,7 @@ pub mod env {
 
 	/// Call into the chain extension provided by the chain if any.
 	/// See [`pallet_revive_uapi::HostFn::call_chain_extension`].
+	#[writes_memory]
 	fn call_chain_extension(
 		&mut self,
 		memory: &mut M,
@@ -1890,6 +2006,7 @@ pub mod env {
 	/// Send an XCM program from the contract to the specified destination.
 	/// See [`pallet_revive_uapi::HostFn::send_xcm`].
 	#[mutating]
+	#[writes_memory]
 	fn xcm_send(
 		&mut self,
 		memory: &mut M,
@@ -1930,6 +2047,9 @@ pub mod env {
 	/// Recovers the ECDSA public key from the given message hash and signature.
 	/// See [`pallet_revive_uapi::HostFn::ecdsa_recover`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[context_safe]
+	#[gas(RuntimeCosts::EcdsaRecovery)]
 	fn ecdsa_recover(
 		&mut self,
 		memory: &mut M,
@@ -1937,8 +2057,6 @@ pub mod env {
 		message_hash_ptr: u32,
 		output_ptr: u32,
 	) -> Result<ReturnErrorCode, TrapReason> {
-		self.charge_gas(RuntimeCosts::EcdsaRecovery)?;
-
 		let mut signature: [u8; 65] = [0; 65];
 		memory.read_into_buf(signature_ptr, &mut signature)?;
 		let mut message_hash: [u8; 32] = [0; 32];
@@ -1961,6 +2079,7 @@ pub mod env {
 	/// Verify a sr25519 signature
 	/// See [`pallet_revive_uapi::HostFn::sr25519_verify`].
 	#[api_version(0)]
+	#[context_safe]
 	fn sr25519_verify(
 		&mut self,
 		memory: &mut M,
@@ -1992,12 +2111,12 @@ pub mod env {
 	/// Disabled until the internal implementation takes care of collecting
 	/// the immutable data of the new code hash.
 	#[mutating]
+	#[gas(RuntimeCosts::SetCodeHash)]
 	fn set_code_hash(
 		&mut self,
 		memory: &mut M,
 		code_hash_ptr: u32,
 	) -> Result<ReturnErrorCode, TrapReason> {
-		self.charge_gas(RuntimeCosts::SetCodeHash)?;
 		let code_hash: H256 = memory.read_h256(code_hash_ptr)?;
 		match self.ext.set_code_hash(code_hash) {
 			Err(err) => {
@@ -2011,13 +2130,15 @@ pub mod env {
 	/// Calculates Ethereum address from the ECDSA compressed public key and stores
 	/// See [`pallet_revive_uapi::HostFn::ecdsa_to_eth_address`].
 	#[api_version(0)]
+	#[writes_memory]
+	#[context_safe]
+	#[gas(RuntimeCosts::EcdsaToEthAddress)]
 	fn ecdsa_to_eth_address(
 		&mut self,
 		memory: &mut M,
 		key_ptr: u32,
 		out_ptr: u32,
 	) -> Result<ReturnErrorCode, TrapReason> {
-		self.charge_gas(RuntimeCosts::EcdsaToEthAddress)?;
 		let mut compressed_key: [u8; 33] = [0; 33];
 		memory.read_into_buf(key_ptr, &mut compressed_key)?;
 		let result = self.ext.ecdsa_to_eth_address(&compressed_key);
@@ -2034,12 +2155,12 @@ pub mod env {
 	/// See [`pallet_revive_uapi::HostFn::lock_delegate_dependency`].
 	#[api_version(0)]
 	#[mutating]
+	#[gas(RuntimeCosts::LockDelegateDependency)]
 	fn lock_delegate_dependency(
 		&mut self,
 		memory: &mut M,
 		code_hash_ptr: u32,
 	) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::LockDelegateDependency)?;
 		let code_hash = memory.read_h256(code_hash_ptr)?;
 		self.ext.lock_delegate_dependency(code_hash)?;
 		Ok(())
@@ -2049,12 +2170,12 @@ pub mod env {
 	/// see [`pallet_revive_uapi::HostFn::unlock_delegate_dependency`].
 	#[api_version(0)]
 	#[mutating]
+	#[gas(RuntimeCosts::UnlockDelegateDependency)]
 	fn unlock_delegate_dependency(
 		&mut self,
 		memory: &mut M,
 		code_hash_ptr: u32,
 	) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::UnlockDelegateDependency)?;
 		let code_hash = memory.read_h256(code_hash_ptr)?;
 		self.ext.unlock_delegate_dependency(&code_hash)?;
 		Ok(())
@@ -2063,6 +2184,7 @@ pub mod env {
 	/// Stores the length of the data returned by the last call into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::return_data_size`].
 	#[api_version(0)]
+	#[writes_memory]
 	fn return_data_size(&mut self, memory: &mut M, out_ptr: u32) -> Result<(), TrapReason> {
 		Ok(self.write_fixed_sandbox_output(
 			memory,
@@ -2076,6 +2198,7 @@ pub mod env {
 	/// Stores data returned by the last call, starting from `offset`, into the supplied buffer.
 	/// See [`pallet_revive_uapi::HostFn::return_data`].
 	#[api_version(0)]
+	#[writes_memory]
 	fn return_data_copy(
 		&mut self,
 		memory: &mut M,